@@ -2,5 +2,17 @@
 
 pub mod prelude;
 
+pub mod admin_user;
+pub mod feed_entry;
+pub mod glossary;
+pub mod job_cursor;
+pub mod job_run;
+pub mod job_schedule;
 pub mod merge_train;
+pub mod migration_guide;
 pub mod milestone_post;
+pub mod prompt_template;
+pub mod release_post;
+pub mod subscription;
+pub mod summary;
+pub mod watched_repo;
@@ -1,4 +1,16 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::admin_user::Entity as AdminUser;
+pub use super::feed_entry::Entity as FeedEntry;
+pub use super::glossary::Entity as Glossary;
+pub use super::job_cursor::Entity as JobCursor;
+pub use super::job_run::Entity as JobRun;
+pub use super::job_schedule::Entity as JobSchedule;
 pub use super::merge_train::Entity as MergeTrain;
+pub use super::migration_guide::Entity as MigrationGuide;
 pub use super::milestone_post::Entity as MilestonePost;
+pub use super::prompt_template::Entity as PromptTemplate;
+pub use super::release_post::Entity as ReleasePost;
+pub use super::subscription::Entity as Subscription;
+pub use super::summary::Entity as Summary;
+pub use super::watched_repo::Entity as WatchedRepo;
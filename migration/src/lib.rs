@@ -2,6 +2,25 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_milestone_posts_table;
 mod m20251107_005257_create_merge_tarin_table;
+mod m20261018_093000_create_feed_entry_table;
+mod m20261018_101500_create_summary_table;
+mod m20261018_113000_create_admin_user_table;
+mod m20261018_120000_add_role_to_admin_user_table;
+mod m20261018_130000_create_job_run_table;
+mod m20261018_140000_create_job_schedule_table;
+mod m20261018_150000_create_job_cursor_table;
+mod m20261018_160000_create_release_post_table;
+mod m20261018_170000_create_migration_guide_table;
+mod m20261018_180000_create_subscription_table;
+mod m20261018_190000_create_watched_repo_table;
+mod m20261018_191000_add_repo_to_job_schedule_table;
+mod m20261018_192000_add_repo_to_release_post_table;
+mod m20261018_193000_add_model_to_job_run_table;
+mod m20261018_200000_create_prompt_template_table;
+mod m20261018_201000_create_glossary_table;
+mod m20261018_202000_rename_qq_thread_id_in_summary_table;
+mod m20261018_203000_add_variants_to_glossary_table;
+mod m20261018_204000_add_source_to_prompt_template_table;
 
 pub struct Migrator;
 
@@ -11,6 +30,25 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_create_milestone_posts_table::Migration),
             Box::new(m20251107_005257_create_merge_tarin_table::Migration),
+            Box::new(m20261018_093000_create_feed_entry_table::Migration),
+            Box::new(m20261018_101500_create_summary_table::Migration),
+            Box::new(m20261018_113000_create_admin_user_table::Migration),
+            Box::new(m20261018_120000_add_role_to_admin_user_table::Migration),
+            Box::new(m20261018_130000_create_job_run_table::Migration),
+            Box::new(m20261018_140000_create_job_schedule_table::Migration),
+            Box::new(m20261018_150000_create_job_cursor_table::Migration),
+            Box::new(m20261018_160000_create_release_post_table::Migration),
+            Box::new(m20261018_170000_create_migration_guide_table::Migration),
+            Box::new(m20261018_180000_create_subscription_table::Migration),
+            Box::new(m20261018_190000_create_watched_repo_table::Migration),
+            Box::new(m20261018_191000_add_repo_to_job_schedule_table::Migration),
+            Box::new(m20261018_192000_add_repo_to_release_post_table::Migration),
+            Box::new(m20261018_193000_add_model_to_job_run_table::Migration),
+            Box::new(m20261018_200000_create_prompt_template_table::Migration),
+            Box::new(m20261018_201000_create_glossary_table::Migration),
+            Box::new(m20261018_202000_rename_qq_thread_id_in_summary_table::Migration),
+            Box::new(m20261018_203000_add_variants_to_glossary_table::Migration),
+            Box::new(m20261018_204000_add_source_to_prompt_template_table::Migration),
         ]
     }
 }
@@ -0,0 +1,52 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FeedEntry::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FeedEntry::Id))
+                    .col(string(FeedEntry::Kind))
+                    .col(string(FeedEntry::Title))
+                    .col(text(FeedEntry::Body))
+                    .col(text(FeedEntry::Links))
+                    .col(date_time(FeedEntry::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx-kind-createdat")
+                .table(FeedEntry::Table)
+                .col(FeedEntry::Kind)
+                .col(FeedEntry::CreatedAt)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FeedEntry::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FeedEntry {
+    Table,
+    Id,
+    Kind,
+    Title,
+    Body,
+    Links,
+    CreatedAt
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Summary::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Summary::Id))
+                    .col(string(Summary::Kind))
+                    .col(string(Summary::Title))
+                    .col(text(Summary::SourceIds))
+                    .col(text(Summary::SourceUrls))
+                    .col(string(Summary::PromptVersion))
+                    .col(string(Summary::Model))
+                    .col(text(Summary::LlmOutput))
+                    .col(text(Summary::PublishResult))
+                    .col(string_null(Summary::QqThreadId))
+                    .col(date_time(Summary::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx-summary-kind-createdat")
+                .table(Summary::Table)
+                .col(Summary::Kind)
+                .col(Summary::CreatedAt)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Summary::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Summary {
+    Table,
+    Id,
+    Kind,
+    Title,
+    SourceIds,
+    SourceUrls,
+    PromptVersion,
+    Model,
+    LlmOutput,
+    PublishResult,
+    QqThreadId,
+    CreatedAt
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminUser::Table)
+                    .if_not_exists()
+                    .col(pk_auto(AdminUser::Id))
+                    .col(string_uniq(AdminUser::Username))
+                    .col(string(AdminUser::PasswordHash))
+                    .col(date_time(AdminUser::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdminUser::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminUser {
+    Table,
+    Id,
+    Username,
+    PasswordHash,
+    CreatedAt
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobRun::Table)
+                    .if_not_exists()
+                    .col(pk_auto(JobRun::Id))
+                    .col(string(JobRun::JobName))
+                    .col(string(JobRun::Trigger))
+                    .col(string(JobRun::Status))
+                    .col(date_time(JobRun::StartedAt))
+                    .col(date_time_null(JobRun::FinishedAt))
+                    .col(text_null(JobRun::Error))
+                    .col(integer_null(JobRun::ItemsProcessed))
+                    .col(big_integer_null(JobRun::LlmLatencyMs))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx-jobrun-jobname-startedat")
+                .table(JobRun::Table)
+                .col(JobRun::JobName)
+                .col(JobRun::StartedAt)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobRun::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobRun {
+    Table,
+    Id,
+    JobName,
+    Trigger,
+    Status,
+    StartedAt,
+    FinishedAt,
+    Error,
+    ItemsProcessed,
+    LlmLatencyMs
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobSchedule::Table)
+                    .if_not_exists()
+                    .col(pk_auto(JobSchedule::Id))
+                    .col(string_uniq(JobSchedule::Name))
+                    .col(string(JobSchedule::Task))
+                    .col(string(JobSchedule::Cron))
+                    .col(boolean(JobSchedule::Enabled))
+                    .col(date_time(JobSchedule::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobSchedule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobSchedule {
+    Table,
+    Id,
+    Name,
+    Task,
+    Cron,
+    Enabled,
+    UpdatedAt
+}
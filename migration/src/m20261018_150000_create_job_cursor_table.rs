@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobCursor::Table)
+                    .if_not_exists()
+                    .col(pk_auto(JobCursor::Id))
+                    .col(string_uniq(JobCursor::JobName))
+                    .col(date_time(JobCursor::HighWaterMark))
+                    .col(date_time(JobCursor::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobCursor::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobCursor {
+    Table,
+    Id,
+    JobName,
+    HighWaterMark,
+    UpdatedAt
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReleasePost::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ReleasePost::Id))
+                    .col(string_uniq(ReleasePost::TagName))
+                    .col(string(ReleasePost::Name))
+                    .col(string(ReleasePost::HtmlUrl))
+                    .col(date_time_null(ReleasePost::PublishedAt))
+                    .col(date_time(ReleasePost::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReleasePost::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReleasePost {
+    Table,
+    Id,
+    TagName,
+    Name,
+    HtmlUrl,
+    PublishedAt,
+    CreatedAt
+}
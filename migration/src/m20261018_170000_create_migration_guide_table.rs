@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MigrationGuide::Table)
+                    .if_not_exists()
+                    .col(pk_auto(MigrationGuide::Id))
+                    .col(string_uniq(MigrationGuide::Milestone))
+                    .col(text(MigrationGuide::PrNumbers))
+                    .col(text(MigrationGuide::Areas))
+                    .col(text(MigrationGuide::Content))
+                    .col(date_time(MigrationGuide::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MigrationGuide::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MigrationGuide {
+    Table,
+    Id,
+    Milestone,
+    PrNumbers,
+    Areas,
+    Content,
+    CreatedAt
+}
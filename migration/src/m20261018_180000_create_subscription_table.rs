@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Subscription::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Subscription::Id))
+                    .col(string_uniq(Subscription::Name))
+                    .col(text(Subscription::Kinds))
+                    .col(text(Subscription::Labels))
+                    .col(text(Subscription::Authors))
+                    .col(text(Subscription::Keywords))
+                    .col(string(Subscription::Publisher))
+                    .col(string(Subscription::Channel))
+                    .col(boolean(Subscription::Enabled))
+                    .col(date_time(Subscription::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Subscription::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Subscription {
+    Table,
+    Id,
+    Name,
+    Kinds,
+    Labels,
+    Authors,
+    Keywords,
+    Publisher,
+    Channel,
+    Enabled,
+    UpdatedAt
+}
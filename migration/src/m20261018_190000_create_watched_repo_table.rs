@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WatchedRepo::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WatchedRepo::Id))
+                    .col(string_uniq(WatchedRepo::FullName))
+                    .col(text(WatchedRepo::Channels))
+                    .col(boolean(WatchedRepo::Enabled))
+                    .col(date_time(WatchedRepo::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WatchedRepo::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WatchedRepo {
+    Table,
+    Id,
+    FullName,
+    Channels,
+    Enabled,
+    UpdatedAt
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// MySQL 中唯一列的索引名称和列名相同
+const TAG_NAME_INDEX: &str = "tag_name";
+const REPO_TAG_NAME_INDEX: &str = "idx_release_post_repo_tag_name";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReleasePost::Table)
+                    // 已有的版本都属于默认仓库
+                    .add_column(string(ReleasePost::Repo).default("bevyengine/bevy"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 不同仓库可能有相同的版本标签
+        manager
+            .drop_index(Index::drop().name(TAG_NAME_INDEX).table(ReleasePost::Table).to_owned())
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(REPO_TAG_NAME_INDEX)
+                    .table(ReleasePost::Table)
+                    .col(ReleasePost::Repo)
+                    .col(ReleasePost::TagName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(REPO_TAG_NAME_INDEX).table(ReleasePost::Table).to_owned())
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(TAG_NAME_INDEX)
+                    .table(ReleasePost::Table)
+                    .col(ReleasePost::TagName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReleasePost::Table)
+                    .drop_column(ReleasePost::Repo)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReleasePost {
+    Table,
+    Repo,
+    TagName
+}
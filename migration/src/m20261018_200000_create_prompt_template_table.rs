@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const NAME_VERSION_INDEX: &str = "idx_prompt_template_name_version";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PromptTemplate::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PromptTemplate::Id))
+                    .col(string(PromptTemplate::Name))
+                    .col(integer(PromptTemplate::Version))
+                    .col(text(PromptTemplate::Content))
+                    .col(date_time(PromptTemplate::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 每次修改都新增一个版本，旧版本保留用于追溯
+        manager
+            .create_index(
+                Index::create()
+                    .name(NAME_VERSION_INDEX)
+                    .table(PromptTemplate::Table)
+                    .col(PromptTemplate::Name)
+                    .col(PromptTemplate::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PromptTemplate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PromptTemplate {
+    Table,
+    Id,
+    Name,
+    Version,
+    Content,
+    CreatedAt
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Glossary::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Glossary::Id))
+                    .col(string_uniq(Glossary::Term))
+                    .col(string(Glossary::Translation))
+                    .col(text_null(Glossary::Explanation))
+                    .col(date_time(Glossary::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Glossary::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Glossary {
+    Table,
+    Id,
+    Term,
+    Translation,
+    Explanation,
+    UpdatedAt
+}
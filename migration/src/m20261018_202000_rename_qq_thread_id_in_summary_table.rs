@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Summary::Table)
+                    // QQ 发帖接口只返回异步任务ID，不是帖子ID
+                    .rename_column(Summary::QqThreadId, Summary::QqTaskId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Summary::Table)
+                    .rename_column(Summary::QqTaskId, Summary::QqThreadId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Summary {
+    Table,
+    QqThreadId,
+    QqTaskId
+}
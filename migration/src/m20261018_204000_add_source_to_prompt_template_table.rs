@@ -0,0 +1,47 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PromptTemplate::Table)
+                    // 版本来源：builtin 为内置模板，admin 为后台修改，内置模板升级时不会覆盖 admin 版本
+                    .add_column(string(PromptTemplate::Source).default("admin"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 启动时先写入内置模板，后台才能修改，所以每个模板最早的版本是内置模板
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE prompt_template SET source = 'builtin' WHERE id IN \
+                 (SELECT id FROM (SELECT MIN(id) AS id FROM prompt_template GROUP BY name) AS first_versions)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PromptTemplate::Table)
+                    .drop_column(PromptTemplate::Source)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PromptTemplate {
+    Table,
+    Source
+}
@@ -0,0 +1,152 @@
+use actix_web::{delete, get, post, web};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    tasks::glossary::GlossaryTerm,
+    util::res::{fail_ret, success_ret},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTermReq {
+    /// 英文术语，匹配时不区分大小写
+    term: String,
+    translation: String,
+    explanation: Option<String>,
+    /// 已知的错误译法，AI 输出中出现时记录警告
+    #[serde(default)]
+    variants: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTermReq {
+    translation: Option<String>,
+    explanation: Option<String>,
+    variants: Option<Vec<String>>,
+}
+
+/// 术语表
+#[get("glossary")]
+pub async fn list_terms(app_state: web::Data<AppState>) -> HttpResult {
+    let terms = entity::glossary::Entity::find()
+        .order_by_asc(entity::glossary::Column::Term)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(terms)
+}
+
+/// 新增术语，下次运行任务时加入提示词
+#[post("glossary", wrap = "RequireRole(Role::Admin)")]
+pub async fn create_term(app_state: web::Data<AppState>, req: web::Json<CreateTermReq>) -> HttpResult {
+    let term = req.term.trim();
+    if term.is_empty() {
+        return fail_ret("术语不能为空");
+    }
+    if req.translation.trim().is_empty() {
+        return fail_ret("译法不能为空");
+    }
+
+    let variants = clean_variants(&req.variants);
+    if variants.iter().any(|variant| req.translation.contains(variant.as_str())) {
+        return fail_ret("错误译法不能是推荐译法的一部分");
+    }
+
+    let exists = find_term(&app_state, term).await?;
+    if exists.is_some() {
+        return fail_ret("术语已存在");
+    }
+
+    let new_term = entity::glossary::ActiveModel {
+        id: NotSet,
+        term: Set(term.to_string()),
+        translation: Set(req.translation.trim().to_string()),
+        explanation: Set(req.explanation.clone().filter(|explanation| !explanation.is_empty())),
+        variants: Set(variants_json(&variants)),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    let term = new_term.insert(&app_state.mysql).await?;
+
+    success_ret(term)
+}
+
+/// 修改译法、说明或错误译法，说明传空字符串、错误译法传空数组时清空
+#[post("glossary/{term}", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_term(
+    app_state: web::Data<AppState>,
+    term: web::Path<String>,
+    req: web::Json<UpdateTermReq>,
+) -> HttpResult {
+    let Some(term) = find_term(&app_state, &term).await? else {
+        return fail_ret("术语不存在");
+    };
+
+    let translation = req.translation.as_deref().unwrap_or(&term.translation).trim().to_string();
+    if translation.is_empty() {
+        return fail_ret("译法不能为空");
+    }
+    let variants = match &req.variants {
+        Some(variants) => clean_variants(variants),
+        None => GlossaryTerm::from_model(term.clone()).variants,
+    };
+    // 错误译法是推荐译法的一部分时，推荐译法本身也会被当作偏离
+    if variants.iter().any(|variant| translation.contains(variant.as_str())) {
+        return fail_ret("错误译法不能是推荐译法的一部分");
+    }
+
+    let mut term = term.into_active_model();
+    term.translation = Set(translation);
+    term.variants = Set(variants_json(&variants));
+    if let Some(explanation) = &req.explanation {
+        term.explanation = Set(Some(explanation.clone()).filter(|explanation| !explanation.is_empty()));
+    }
+    term.updated_at = Set(Utc::now().naive_utc());
+
+    let term = term.update(&app_state.mysql).await?;
+
+    success_ret(term)
+}
+
+#[delete("glossary/{term}", wrap = "RequireRole(Role::Admin)")]
+pub async fn delete_term(app_state: web::Data<AppState>, term: web::Path<String>) -> HttpResult {
+    let Some(term) = find_term(&app_state, &term).await? else {
+        return fail_ret("术语不存在");
+    };
+
+    entity::glossary::Entity::delete_by_id(term.id)
+        .exec(&app_state.mysql)
+        .await?;
+
+    success_ret("")
+}
+
+fn clean_variants(variants: &[String]) -> Vec<String> {
+    variants
+        .iter()
+        .map(|variant| variant.trim().to_string())
+        .filter(|variant| !variant.is_empty())
+        .collect()
+}
+
+/// 错误译法保存为 JSON 数组，没有时为空
+fn variants_json(variants: &[String]) -> Option<String> {
+    if variants.is_empty() {
+        return None;
+    }
+
+    serde_json::to_string(variants).ok()
+}
+
+async fn find_term(app_state: &AppState, term: &str) -> crate::Result<Option<entity::glossary::Model>> {
+    let term = entity::glossary::Entity::find()
+        .filter(entity::glossary::Column::Term.eq(term))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(term)
+}
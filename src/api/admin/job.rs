@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use actix_web::{delete, get, post, web};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    tasks::{
+        github_task::repo::{DEFAULT_REPO, load_repo},
+        job_registry::parse_schedule,
+        task_run::TaskName,
+    },
+    util::res::{fail_ret, success_ret},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateJobReq {
+    name: String,
+    task: String,
+    cron: String,
+    enabled: Option<bool>,
+    /// `owner/repo`，不传时使用默认仓库
+    repo: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateJobReq {
+    cron: Option<String>,
+    enabled: Option<bool>,
+}
+
+/// 定时任务列表
+#[get("jobs")]
+pub async fn list_jobs(app_state: web::Data<AppState>) -> HttpResult {
+    let jobs = entity::job_schedule::Entity::find()
+        .order_by_asc(entity::job_schedule::Column::Id)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(jobs)
+}
+
+/// 新增定时任务，同一个任务可以注册多个不同时间的定时任务
+#[post("jobs", wrap = "RequireRole(Role::Admin)")]
+pub async fn create_job(app_state: web::Data<AppState>, req: web::Json<CreateJobReq>) -> HttpResult {
+    if req.name.is_empty() {
+        return fail_ret("名称不能为空");
+    }
+    let Ok(task) = TaskName::from_str(&req.task) else {
+        return fail_ret("任务不存在");
+    };
+    if parse_schedule(&req.cron).is_err() {
+        return fail_ret("定时表达式错误");
+    }
+    if let Some(repo) = &req.repo {
+        if repo != DEFAULT_REPO && !task.supports_repo() {
+            return fail_ret("该任务只能用于默认仓库");
+        }
+        if load_repo(&app_state, Some(repo)).await.is_err() {
+            return fail_ret("仓库未配置或已停用");
+        }
+    }
+
+    let exists = find_job(&app_state, &req.name).await?;
+    if exists.is_some() {
+        return fail_ret("名称已存在");
+    }
+
+    let new_job = entity::job_schedule::ActiveModel {
+        id: NotSet,
+        name: Set(req.name.clone()),
+        task: Set(task.as_str().to_string()),
+        cron: Set(req.cron.clone()),
+        enabled: Set(req.enabled.unwrap_or(true)),
+        updated_at: Set(Utc::now().naive_utc()),
+        repo: Set(req.repo.clone()),
+    };
+    let job = new_job.insert(&app_state.mysql).await?;
+
+    success_ret(job)
+}
+
+/// 修改定时时间或启用状态，下一次调度时生效
+#[post("jobs/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_job(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    req: web::Json<UpdateJobReq>,
+) -> HttpResult {
+    let Some(job) = find_job(&app_state, &name).await? else {
+        return fail_ret("定时任务不存在");
+    };
+
+    let mut job = job.into_active_model();
+    if let Some(cron) = &req.cron {
+        if parse_schedule(cron).is_err() {
+            return fail_ret("定时表达式错误");
+        }
+        job.cron = Set(cron.clone());
+    }
+    if let Some(enabled) = req.enabled {
+        job.enabled = Set(enabled);
+    }
+    job.updated_at = Set(Utc::now().naive_utc());
+
+    let job = job.update(&app_state.mysql).await?;
+
+    success_ret(job)
+}
+
+#[delete("jobs/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn delete_job(app_state: web::Data<AppState>, name: web::Path<String>) -> HttpResult {
+    let Some(job) = find_job(&app_state, &name).await? else {
+        return fail_ret("定时任务不存在");
+    };
+
+    entity::job_schedule::Entity::delete_by_id(job.id)
+        .exec(&app_state.mysql)
+        .await?;
+
+    success_ret("")
+}
+
+async fn find_job(app_state: &AppState, name: &str) -> crate::Result<Option<entity::job_schedule::Model>> {
+    let job = entity::job_schedule::Entity::find()
+        .filter(entity::job_schedule::Column::Name.eq(name))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(job)
+}
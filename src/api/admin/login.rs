@@ -1,10 +1,154 @@
-use actix_web::post;
+use std::env;
 
-use crate::{util::res::success_ret, HttpResult};
+use actix_web::{HttpRequest, http::header::HeaderMap, post, web};
+use chrono::Utc;
+use log::info;
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
 
+use crate::{
+    AppState, HttpResult,
+    api::admin::role::{LoginUser, Role},
+    util::{
+        cache::{del, put_ttl},
+        password::{hash_password, verify_password},
+        random_str,
+        res::{fail_ret, success_ret},
+    },
+};
+
+// 登录有效期
+const SESSION_TTL_SEC: u64 = 60 * 60 * 24;
+const TOKEN_LEN: usize = 48;
+
+#[derive(Debug, Deserialize)]
+pub struct LoginReq {
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginRes {
+    token: String,
+    expires_in: u64,
+}
 
 #[post("login")]
-pub async fn login() -> HttpResult {
+pub async fn login(app_state: web::Data<AppState>, req: web::Json<LoginReq>) -> HttpResult {
+    let user = entity::admin_user::Entity::find()
+        .filter(entity::admin_user::Column::Username.eq(&req.username))
+        .one(&app_state.mysql)
+        .await?;
+
+    let Some(user) = user else {
+        return fail_ret("用户名或密码错误");
+    };
+
+    if !verify_password(&req.password, &user.password_hash) {
+        return fail_ret("用户名或密码错误");
+    }
+
+    let token = create_session(&app_state, user.id).await?;
+
+    success_ret(LoginRes { token, expires_in: SESSION_TTL_SEC })
+}
+
+#[post("logout")]
+pub async fn logout(app_state: web::Data<AppState>, request: HttpRequest) -> HttpResult {
+    let token = get_token(request.headers());
+    del(&app_state.redis, &session_key(token)).await?;
 
     success_ret("")
-}
\ No newline at end of file
+}
+
+/// 换发新的 token，旧 token 立即失效
+#[post("refresh")]
+pub async fn refresh(
+    app_state: web::Data<AppState>,
+    request: HttpRequest,
+    login_user: web::ReqData<LoginUser>,
+) -> HttpResult {
+    let token = create_session(&app_state, login_user.id).await?;
+
+    let old_token = get_token(request.headers());
+    del(&app_state.redis, &session_key(old_token)).await?;
+
+    success_ret(LoginRes { token, expires_in: SESSION_TTL_SEC })
+}
+
+async fn create_session(app_state: &AppState, user_id: i32) -> anyhow::Result<String> {
+    let token = random_str(TOKEN_LEN);
+    put_ttl(&app_state.redis, &session_key(&token), &user_id.to_string(), SESSION_TTL_SEC).await?;
+
+    Ok(token)
+}
+
+/// 登录状态在 Redis 中的键
+pub fn session_key(token: &str) -> String {
+    format!("admin_session_{}", token)
+}
+
+/// 读取 `Authorization` 请求头，支持 `Bearer <token>` 和直接传 token
+pub fn get_token(headers: &HeaderMap) -> &str {
+    let token = headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    token.strip_prefix("Bearer ").unwrap_or(token).trim()
+}
+
+/// 没有管理员时，使用 `ADMIN_USERNAME` 和 `ADMIN_PASSWORD` 创建第一个管理员
+pub async fn init_admin_user(app_state: &AppState) -> anyhow::Result<()> {
+    let count = entity::admin_user::Entity::find()
+        .count(&app_state.mysql)
+        .await?;
+
+    if count > 0 {
+        return Ok(());
+    }
+
+    let (Ok(username), Ok(password)) = (env::var("ADMIN_USERNAME"), env::var("ADMIN_PASSWORD")) else {
+        return Ok(());
+    };
+
+    if username.is_empty() || password.is_empty() {
+        return Ok(());
+    }
+
+    let new_user = entity::admin_user::ActiveModel {
+        id: NotSet,
+        username: Set(username.clone()),
+        password_hash: Set(hash_password(&password)?),
+        role: Set(Role::Admin.as_str().to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+    new_user.insert(&app_state.mysql).await?;
+
+    info!("已创建管理员: {}", username);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use crate::api::admin::login::get_token;
+
+    #[test]
+    fn test_get_token() {
+        let request = TestRequest::default()
+            .insert_header(("Authorization", "Bearer abc"))
+            .to_http_request();
+        assert_eq!(get_token(request.headers()), "abc");
+
+        let request = TestRequest::default()
+            .insert_header(("Authorization", "abc"))
+            .to_http_request();
+        assert_eq!(get_token(request.headers()), "abc");
+
+        let request = TestRequest::default().to_http_request();
+        assert_eq!(get_token(request.headers()), "");
+    }
+}
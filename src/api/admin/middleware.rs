@@ -1,25 +1,34 @@
 use std::{
-    future::{ready, Ready}
+    future::{ready, Ready},
+    rc::Rc,
+    str::FromStr,
 };
 
 use actix_web::{
     body::EitherBody,
     dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
-    http::header::HeaderValue,
     web::Data,
     Error, HttpMessage, HttpResponse,
 };
-use futures_util::future::{LocalBoxFuture, ok};
+use futures_util::future::LocalBoxFuture;
 use log::error;
-use redis::Commands;
-
-use crate::{AppState, util::cache::get_prefix_key};
+use sea_orm::EntityTrait;
+
+use crate::{
+    AppState,
+    api::admin::{
+        login::{get_token, session_key},
+        role::{LoginUser, Role},
+    },
+    util::{cache::get, res::Res},
+};
 
+/// 校验登录状态，并把 `LoginUser` 放入请求扩展
 pub struct CheckLogin;
 
 impl<S, B> Transform<S, ServiceRequest> for CheckLogin
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -30,16 +39,16 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(CheckLoginMiddleware { service }))
+        ready(Ok(CheckLoginMiddleware { service: Rc::new(service) }))
     }
 }
 pub struct CheckLoginMiddleware<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for CheckLoginMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -50,76 +59,162 @@ where
     dev::forward_ready!(service);
 
     fn call(&self, request: ServiceRequest) -> Self::Future {
-        #[cfg(debug_assertions)]
-        {
-            // 测试跳过验证
-            request.extensions_mut().insert(1i32);
-            let res = self.service.call(request);
-            return Box::pin(async move {
-                // forwarded responses map to "left" body
-                res.await.map(ServiceResponse::map_into_left_body)
-            })
-        }
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let login_user = match check_login(&request).await {
+                Ok(login_user) => login_user,
+                Err(err) => {
+                    error!("{err:?}");
+                    None
+                }
+            };
+
+            let Some(login_user) = login_user else {
+                let response = HttpResponse::Unauthorized()
+                    .json(deny_body("未登录或登录已过期"))
+                    .map_into_right_body();
+                return Ok(request.into_response(response));
+            };
+
+            request.extensions_mut().insert(login_user);
+
+            // forwarded responses map to "left" body
+            service.call(request).await.map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
 
-        let (request, is_login) = check_login(request);
-        if is_login {
-            let res = self.service.call(request);
-            Box::pin(async move {
-                // forwarded responses map to "left" body
-                res.await.map(ServiceResponse::map_into_left_body)
-            })
-        } else {
-            let response = HttpResponse::Unauthorized()
-                .finish()
-                .map_into_right_body();
-            Box::pin(
-                ok(request.into_response(response))
-            )
-        }
+/// 要求登录账号至少拥有指定角色，需要放在 `CheckLogin` 内层
+///
+/// ```ignore
+/// #[post("tasks/{name}/run", wrap = "RequireRole(Role::Operator)")]
+/// ```
+pub struct RequireRole(pub Role);
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequireRoleMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware { service, role: self.0 }))
     }
 }
+pub struct RequireRoleMiddleware<S> {
+    service: S,
+    role: Role,
+}
 
-fn check_login(request: ServiceRequest) -> (ServiceRequest, bool) {
-    let path = request.path().to_string();
-    if path == "/api/admin/login" {
-        return (request, true);
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let login_user = request.extensions().get::<LoginUser>().copied();
+
+        let response = match login_user {
+            None => HttpResponse::Unauthorized().json(deny_body("未登录或登录已过期")),
+            Some(login_user) if !login_user.has_role(self.role) => {
+                HttpResponse::Forbidden().json(deny_body("权限不足"))
+            }
+            Some(_) => {
+                let res = self.service.call(request);
+                return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
+            }
+        };
+
+        let response = request.into_response(response.map_into_right_body());
+        Box::pin(async { Ok(response) })
+    }
+}
+
+fn deny_body(msg: &str) -> Res<&'static str> {
+    Res {
+        code: -1,
+        msg: msg.to_string(),
+        data: "",
+    }
+}
+
+/// 根据 token 读取登录的账号和角色
+async fn check_login(request: &ServiceRequest) -> anyhow::Result<Option<LoginUser>> {
+    let token = get_token(request.headers());
+    if token.is_empty() {
+        return Ok(None);
     }
 
-    // 默认值
-    let default_val = HeaderValue::from_str("").unwrap();
     // 永远不会是None
     let app_state = request.app_data::<Data<AppState>>().unwrap();
 
-    let token = request
-        .headers()
-        .get("Authorization")
-        .unwrap_or(&default_val)
-        .to_str()
-        .unwrap_or("");
-
-    // 读取登录状态
-    let mut redis = match app_state.redis.get_connection() {
-        Ok(redis) => redis,
-        Err(err) => {
-            error!("{}", err);
-            return (request, false);
-        }
+    let Some(user_id) = get(&app_state.redis, &session_key(token)).await? else {
+        return Ok(None);
+    };
+    let Ok(user_id) = user_id.parse::<i32>() else {
+        return Ok(None);
     };
 
-    let token_key = get_prefix_key(token);
+    // 每次请求都读取角色，修改角色后立即生效
+    let Some(user) = entity::admin_user::Entity::find_by_id(user_id)
+        .one(&app_state.mysql)
+        .await? else {
+        return Ok(None);
+    };
 
-    let user_id: Option<i32> = match redis.get(token_key) {
-        Ok(user_id) => user_id,
-        Err(err) => {
-            error!("{}", err);
-            return (request, false);
-        }
+    Ok(Some(LoginUser {
+        id: user.id,
+        role: Role::from_str(&user.role)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpMessage, HttpResponse, dev::Service, get, http::StatusCode, test};
+
+    use crate::api::admin::{
+        middleware::RequireRole,
+        role::{LoginUser, Role},
     };
 
-    if let Some(user_id) = user_id {
-        request.extensions_mut().insert(user_id);
-        (request, true)
-    } else {
-        (request, false)
+    #[get("/run", wrap = "RequireRole(Role::Operator)")]
+    async fn run() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn test_require_role() {
+        let app = test::init_service(App::new().service(run)).await;
+
+        let cases = [
+            (None, StatusCode::UNAUTHORIZED),
+            (Some(Role::Viewer), StatusCode::FORBIDDEN),
+            (Some(Role::Operator), StatusCode::OK),
+            (Some(Role::Admin), StatusCode::OK),
+        ];
+
+        for (role, status) in cases {
+            let request = test::TestRequest::get().uri("/run").to_request();
+            if let Some(role) = role {
+                request.extensions_mut().insert(LoginUser { id: 1, role });
+            }
+
+            let res = app.call(request).await.unwrap();
+            assert_eq!(res.status(), status);
+        }
     }
 }
@@ -1,13 +1,53 @@
 use actix_web::{Scope, web};
+mod glossary;
+mod job;
 mod login;
 mod middleware;
+mod prompt;
+mod repo;
+mod role;
+mod subscription;
+mod task;
+mod user;
+
+pub use login::init_admin_user;
 
 pub fn admin() -> Scope {
     web::scope("admin")
         .service(login::login)
         .service(
             // 把需要权限验证的接口放在一起
+            // 具体接口需要的角色通过 `RequireRole` 设置，默认所有角色都可以访问
             web::scope("manager")
             .wrap(middleware::CheckLogin)
+            .service(login::logout)
+            .service(login::refresh)
+            .service(user::me)
+            .service(user::list_users)
+            .service(user::create_user)
+            .service(user::update_role)
+            .service(job::list_jobs)
+            .service(job::create_job)
+            .service(job::update_job)
+            .service(job::delete_job)
+            .service(repo::list_repos)
+            .service(repo::create_repo)
+            .service(repo::update_repo)
+            .service(repo::delete_repo)
+            .service(subscription::list_subscriptions)
+            .service(subscription::create_subscription)
+            .service(subscription::update_subscription)
+            .service(subscription::delete_subscription)
+            .service(prompt::list_prompts)
+            .service(prompt::prompt_versions)
+            .service(prompt::update_prompt)
+            .service(prompt::restore_prompt)
+            .service(glossary::list_terms)
+            .service(glossary::create_term)
+            .service(glossary::update_term)
+            .service(glossary::delete_term)
+            .service(task::run_task)
+            .service(task::list_runs)
+            .service(task::run_status)
         )
 }
@@ -0,0 +1,79 @@
+use actix_web::{get, post, web};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    tasks::prompt_template::{DEFAULT_TEMPLATES, is_known_template, load_template, save_new_version},
+    util::res::{fail_ret, success_ret},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePromptReq {
+    /// 可以使用 `{date}`、`{repo}`、`{items}` 变量，`{items}` 是条目数量
+    content: String,
+}
+
+/// 所有模板当前使用的版本
+#[get("prompts")]
+pub async fn list_prompts(app_state: web::Data<AppState>) -> HttpResult {
+    let mut list = Vec::new();
+    for (name, _, _) in DEFAULT_TEMPLATES {
+        list.push(load_template(&app_state, name).await?);
+    }
+
+    success_ret(list)
+}
+
+/// 模板的历史版本，最新的在前
+#[get("prompts/{name}")]
+pub async fn prompt_versions(app_state: web::Data<AppState>, name: web::Path<String>) -> HttpResult {
+    if !is_known_template(&name) {
+        return fail_ret("模板不存在");
+    }
+
+    let versions = entity::prompt_template::Entity::find()
+        .filter(entity::prompt_template::Column::Name.eq(name.as_str()))
+        .order_by_desc(entity::prompt_template::Column::Version)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(versions)
+}
+
+/// 修改模板，保存为新版本，下次运行任务时生效
+#[post("prompts/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_prompt(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    req: web::Json<UpdatePromptReq>,
+) -> HttpResult {
+    if !is_known_template(&name) {
+        return fail_ret("模板不存在");
+    }
+    if req.content.trim().is_empty() {
+        return fail_ret("模板内容不能为空");
+    }
+
+    let template = save_new_version(&app_state, &name, req.content.trim_end()).await?;
+
+    success_ret(template)
+}
+
+/// 恢复到某个历史版本，复制为新版本，保证版本号只增不减
+#[post("prompts/{name}/versions/{version}/restore", wrap = "RequireRole(Role::Admin)")]
+pub async fn restore_prompt(app_state: web::Data<AppState>, path: web::Path<(String, i32)>) -> HttpResult {
+    let (name, version) = path.into_inner();
+    let Some(old) = entity::prompt_template::Entity::find()
+        .filter(entity::prompt_template::Column::Name.eq(&name))
+        .filter(entity::prompt_template::Column::Version.eq(version))
+        .one(&app_state.mysql)
+        .await? else {
+        return fail_ret("模板版本不存在");
+    };
+
+    let template = save_new_version(&app_state, &name, &old.content).await?;
+
+    success_ret(template)
+}
@@ -0,0 +1,176 @@
+use std::{collections::HashMap, str::FromStr};
+
+use actix_web::{delete, get, post, web};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    tasks::{github_task::repo::{GithubRepo, parse_channels}, job_registry::parse_schedule, task_run::TaskName},
+    util::res::{fail_ret, success_ret},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRepoReq {
+    /// `owner/repo`
+    full_name: String,
+    /// 发布渠道 -> 总结类型 -> 频道，例如 `{"discord": {"issues": "https://discord.com/api/webhooks/..."}}`，
+    /// 也兼容只配置 QQ 子频道的旧格式 `{"issues": "719710382"}`
+    #[serde(default)]
+    channels: HashMap<String, Value>,
+    enabled: Option<bool>,
+    /// 同时为这些任务创建定时任务，例如 `["issues", "prs"]`
+    #[serde(default)]
+    watchers: Vec<String>,
+    cron: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRepoReq {
+    channels: Option<HashMap<String, Value>>,
+    enabled: Option<bool>,
+}
+
+/// 监听的仓库列表，频道中可能有 Discord Webhook 地址，只有管理员可以查看
+#[get("repos", wrap = "RequireRole(Role::Admin)")]
+pub async fn list_repos(app_state: web::Data<AppState>) -> HttpResult {
+    let repos = entity::watched_repo::Entity::find()
+        .order_by_asc(entity::watched_repo::Column::Id)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(repos)
+}
+
+/// 新增监听的仓库，可以同时创建该仓库的定时任务，任务名称为 `任务@owner/repo`
+#[post("repos", wrap = "RequireRole(Role::Admin)")]
+pub async fn create_repo(app_state: web::Data<AppState>, req: web::Json<CreateRepoReq>) -> HttpResult {
+    let Ok(repo) = GithubRepo::parse(&req.full_name) else {
+        return fail_ret("仓库名称格式错误");
+    };
+    if !valid_channels(&req.channels) {
+        return fail_ret("频道配置错误");
+    }
+
+    let mut watchers = Vec::new();
+    for watcher in &req.watchers {
+        let Ok(task) = TaskName::from_str(watcher) else {
+            return fail_ret("任务不存在");
+        };
+        if !repo.is_default() && !task.supports_repo() {
+            return fail_ret("该任务只能用于默认仓库");
+        }
+        watchers.push(task);
+    }
+    if !watchers.is_empty() && req.cron.as_deref().is_none_or(|cron| parse_schedule(cron).is_err()) {
+        return fail_ret("定时表达式错误");
+    }
+
+    let exists = find_repo(&app_state, &repo.full_name()).await?;
+    if exists.is_some() {
+        return fail_ret("仓库已存在");
+    }
+
+    let new_repo = entity::watched_repo::ActiveModel {
+        id: NotSet,
+        full_name: Set(repo.full_name()),
+        channels: Set(serde_json::to_string(&req.channels)?),
+        enabled: Set(req.enabled.unwrap_or(true)),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    let model = new_repo.insert(&app_state.mysql).await?;
+
+    for task in watchers {
+        let job_name = repo.job_name(task);
+        let exists = entity::job_schedule::Entity::find()
+            .filter(entity::job_schedule::Column::Name.eq(&job_name))
+            .one(&app_state.mysql)
+            .await?;
+        if exists.is_some() {
+            continue;
+        }
+
+        let new_job = entity::job_schedule::ActiveModel {
+            id: NotSet,
+            name: Set(job_name),
+            task: Set(task.as_str().to_string()),
+            cron: Set(req.cron.clone().unwrap_or_default()),
+            enabled: Set(true),
+            updated_at: Set(Utc::now().naive_utc()),
+            repo: Set(Some(repo.full_name())),
+        };
+        new_job.insert(&app_state.mysql).await?;
+    }
+
+    success_ret(model)
+}
+
+/// 修改频道或启用状态，停用后该仓库的定时任务和 Webhook 事件都不再处理
+#[post("repos/{owner}/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_repo(
+    app_state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    req: web::Json<UpdateRepoReq>,
+) -> HttpResult {
+    let (owner, name) = path.into_inner();
+    let Some(repo) = find_repo(&app_state, &format!("{}/{}", owner, name)).await? else {
+        return fail_ret("仓库不存在");
+    };
+
+    let mut repo = repo.into_active_model();
+    if let Some(channels) = &req.channels {
+        if !valid_channels(channels) {
+            return fail_ret("频道配置错误");
+        }
+        repo.channels = Set(serde_json::to_string(channels)?);
+    }
+    if let Some(enabled) = req.enabled {
+        repo.enabled = Set(enabled);
+    }
+    repo.updated_at = Set(Utc::now().naive_utc());
+
+    let repo = repo.update(&app_state.mysql).await?;
+
+    success_ret(repo)
+}
+
+/// 删除仓库和它的定时任务，默认仓库只能停用
+#[delete("repos/{owner}/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn delete_repo(app_state: web::Data<AppState>, path: web::Path<(String, String)>) -> HttpResult {
+    let (owner, name) = path.into_inner();
+    let Some(repo) = find_repo(&app_state, &format!("{}/{}", owner, name)).await? else {
+        return fail_ret("仓库不存在");
+    };
+    if GithubRepo::parse(&repo.full_name).is_ok_and(|repo| repo.is_default()) {
+        return fail_ret("默认仓库不能删除");
+    }
+
+    entity::job_schedule::Entity::delete_many()
+        .filter(entity::job_schedule::Column::Repo.eq(&repo.full_name))
+        .exec(&app_state.mysql)
+        .await?;
+
+    entity::watched_repo::Entity::delete_by_id(repo.id)
+        .exec(&app_state.mysql)
+        .await?;
+
+    success_ret("")
+}
+
+fn valid_channels(channels: &HashMap<String, Value>) -> bool {
+    serde_json::to_string(channels).is_ok_and(|channels| parse_channels(&channels).is_ok())
+}
+
+async fn find_repo(app_state: &AppState, full_name: &str) -> crate::Result<Option<entity::watched_repo::Model>> {
+    let repo = entity::watched_repo::Entity::find()
+        .filter(entity::watched_repo::Column::FullName.eq(full_name))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(repo)
+}
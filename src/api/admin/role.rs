@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// 后台账号角色，权限从低到高
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 只能查看任务记录
+    Viewer,
+    /// 可以手动触发任务
+    Operator,
+    /// 可以修改定时配置、提示词、频道绑定和账号
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "viewer" => Ok(Role::Viewer),
+            "operator" => Ok(Role::Operator),
+            "admin" => Ok(Role::Admin),
+            _ => anyhow::bail!("未知的角色: {}", s),
+        }
+    }
+}
+
+/// 当前登录的账号，由 `CheckLogin` 放入请求扩展
+#[derive(Debug, Clone, Copy)]
+pub struct LoginUser {
+    pub id: i32,
+    pub role: Role,
+}
+
+impl LoginUser {
+    pub fn has_role(&self, role: Role) -> bool {
+        self.role >= role
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::api::admin::role::{LoginUser, Role};
+
+    #[test]
+    fn test_role() {
+        assert_eq!(Role::from_str("operator").unwrap(), Role::Operator);
+        assert!(Role::from_str("root").is_err());
+
+        let user = LoginUser { id: 1, role: Role::Operator };
+        assert!(user.has_role(Role::Viewer));
+        assert!(user.has_role(Role::Operator));
+        assert!(!user.has_role(Role::Admin));
+    }
+}
@@ -0,0 +1,154 @@
+use actix_web::{delete, get, post, web};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    bots::publisher::CHANNEL_PUBLISHERS,
+    tasks::subscription::SubscriptionFilter,
+    util::res::{fail_ret, success_ret},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSubscriptionReq {
+    name: String,
+    #[serde(flatten)]
+    filter: SubscriptionFilter,
+    publisher: String,
+    channel: String,
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSubscriptionReq {
+    kinds: Option<Vec<String>>,
+    labels: Option<Vec<String>>,
+    authors: Option<Vec<String>>,
+    keywords: Option<Vec<String>>,
+    channel: Option<String>,
+    enabled: Option<bool>,
+}
+
+/// 订阅列表，频道可能是 Discord Webhook 地址等凭据，只有管理员可以查看
+#[get("subscriptions", wrap = "RequireRole(Role::Admin)")]
+pub async fn list_subscriptions(app_state: web::Data<AppState>) -> HttpResult {
+    let subscriptions = entity::subscription::Entity::find()
+        .order_by_asc(entity::subscription::Column::Id)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(subscriptions)
+}
+
+/// 新增订阅，匹配的 Issue 和 PR 会在每日总结后发送到订阅的频道
+#[post("subscriptions", wrap = "RequireRole(Role::Admin)")]
+pub async fn create_subscription(app_state: web::Data<AppState>, req: web::Json<CreateSubscriptionReq>) -> HttpResult {
+    if req.name.is_empty() {
+        return fail_ret("名称不能为空");
+    }
+    if !CHANNEL_PUBLISHERS.contains(&req.publisher.as_str()) {
+        return fail_ret("发布渠道不支持订阅");
+    }
+    if req.channel.is_empty() {
+        return fail_ret("频道不能为空");
+    }
+    if let Err(err) = req.filter.validate() {
+        return fail_ret(&err.to_string());
+    }
+
+    let exists = find_subscription(&app_state, &req.name).await?;
+    if exists.is_some() {
+        return fail_ret("名称已存在");
+    }
+
+    let new_subscription = entity::subscription::ActiveModel {
+        id: NotSet,
+        name: Set(req.name.clone()),
+        kinds: Set(serde_json::to_string(&req.filter.kinds)?),
+        labels: Set(serde_json::to_string(&req.filter.labels)?),
+        authors: Set(serde_json::to_string(&req.filter.authors)?),
+        keywords: Set(serde_json::to_string(&req.filter.keywords)?),
+        publisher: Set(req.publisher.clone()),
+        channel: Set(req.channel.clone()),
+        enabled: Set(req.enabled.unwrap_or(true)),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    let subscription = new_subscription.insert(&app_state.mysql).await?;
+
+    success_ret(subscription)
+}
+
+/// 修改筛选条件、频道或启用状态，下一次总结时生效
+#[post("subscriptions/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_subscription(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    req: web::Json<UpdateSubscriptionReq>,
+) -> HttpResult {
+    let Some(subscription) = find_subscription(&app_state, &name).await? else {
+        return fail_ret("订阅不存在");
+    };
+
+    let mut filter = SubscriptionFilter::from_model(&subscription)?;
+    if let Some(kinds) = &req.kinds {
+        filter.kinds = kinds.clone();
+    }
+    if let Some(labels) = &req.labels {
+        filter.labels = labels.clone();
+    }
+    if let Some(authors) = &req.authors {
+        filter.authors = authors.clone();
+    }
+    if let Some(keywords) = &req.keywords {
+        filter.keywords = keywords.clone();
+    }
+    if let Err(err) = filter.validate() {
+        return fail_ret(&err.to_string());
+    }
+
+    let mut subscription = subscription.into_active_model();
+    subscription.kinds = Set(serde_json::to_string(&filter.kinds)?);
+    subscription.labels = Set(serde_json::to_string(&filter.labels)?);
+    subscription.authors = Set(serde_json::to_string(&filter.authors)?);
+    subscription.keywords = Set(serde_json::to_string(&filter.keywords)?);
+    if let Some(channel) = &req.channel {
+        if channel.is_empty() {
+            return fail_ret("频道不能为空");
+        }
+        subscription.channel = Set(channel.clone());
+    }
+    if let Some(enabled) = req.enabled {
+        subscription.enabled = Set(enabled);
+    }
+    subscription.updated_at = Set(Utc::now().naive_utc());
+
+    let subscription = subscription.update(&app_state.mysql).await?;
+
+    success_ret(subscription)
+}
+
+#[delete("subscriptions/{name}", wrap = "RequireRole(Role::Admin)")]
+pub async fn delete_subscription(app_state: web::Data<AppState>, name: web::Path<String>) -> HttpResult {
+    let Some(subscription) = find_subscription(&app_state, &name).await? else {
+        return fail_ret("订阅不存在");
+    };
+
+    entity::subscription::Entity::delete_by_id(subscription.id)
+        .exec(&app_state.mysql)
+        .await?;
+
+    success_ret("")
+}
+
+async fn find_subscription(app_state: &AppState, name: &str) -> crate::Result<Option<entity::subscription::Model>> {
+    let subscription = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::Name.eq(name))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(subscription)
+}
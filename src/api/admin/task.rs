@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use actix_web::{get, post, web};
+use chrono::NaiveDateTime;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{middleware::RequireRole, role::Role},
+    tasks::{
+        github_task::repo::DEFAULT_REPO,
+        task_run::{TaskName, spawn_manual_run},
+        time_window::TimeWindow,
+    },
+    util::res::{ListRes, fail_ret, success_ret},
+};
+
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// 时间范围，RFC3339 格式，都不传时从任务游标处理到现在
+#[derive(Debug, Deserialize)]
+pub struct RunTaskQuery {
+    since: Option<String>,
+    until: Option<String>,
+    /// `owner/repo`，不传时使用默认仓库
+    repo: Option<String>,
+}
+
+/// 运行记录筛选条件，时间格式为 `2025-11-10 12:00:00`（UTC）
+#[derive(Debug, Deserialize)]
+pub struct ListRunsQuery {
+    job: Option<String>,
+    status: Option<String>,
+    trigger: Option<String>,
+    started_after: Option<String>,
+    started_before: Option<String>,
+    page: Option<u64>,
+    page_size: Option<u64>,
+}
+
+/// 手动触发任务，返回运行记录
+#[post("tasks/{name}/run", wrap = "RequireRole(Role::Operator)")]
+pub async fn run_task(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    query: web::Query<RunTaskQuery>,
+) -> HttpResult {
+    let Ok(name) = TaskName::from_str(&name) else {
+        return fail_ret("任务不存在");
+    };
+
+    if query.repo.as_deref().is_some_and(|repo| repo != DEFAULT_REPO) && !name.supports_repo() {
+        return fail_ret("该任务只能用于默认仓库");
+    }
+
+    let has_window = query.since.is_some() || query.until.is_some();
+    if has_window && !name.supports_window() {
+        return fail_ret("该任务不支持指定时间范围");
+    }
+
+    let window = if has_window {
+        let Ok(window) = TimeWindow::parse(query.since.as_deref(), query.until.as_deref()) else {
+            return fail_ret("时间范围格式错误");
+        };
+        Some(window)
+    } else {
+        None
+    };
+
+    let run = spawn_manual_run(app_state.get_ref().clone(), name, query.repo.as_deref(), window).await?;
+
+    success_ret(run)
+}
+
+/// 运行记录列表，最新的在前
+#[get("tasks/runs")]
+pub async fn list_runs(app_state: web::Data<AppState>, query: web::Query<ListRunsQuery>) -> HttpResult {
+    let mut select = entity::job_run::Entity::find();
+
+    if let Some(job) = &query.job {
+        select = select.filter(entity::job_run::Column::JobName.eq(job));
+    }
+    if let Some(status) = &query.status {
+        select = select.filter(entity::job_run::Column::Status.eq(status));
+    }
+    if let Some(trigger) = &query.trigger {
+        select = select.filter(entity::job_run::Column::Trigger.eq(trigger));
+    }
+    if let Some(started_after) = &query.started_after {
+        let Ok(started_after) = NaiveDateTime::parse_from_str(started_after, "%Y-%m-%d %H:%M:%S") else {
+            return fail_ret("时间格式错误");
+        };
+        select = select.filter(entity::job_run::Column::StartedAt.gte(started_after));
+    }
+    if let Some(started_before) = &query.started_before {
+        let Ok(started_before) = NaiveDateTime::parse_from_str(started_before, "%Y-%m-%d %H:%M:%S") else {
+            return fail_ret("时间格式错误");
+        };
+        select = select.filter(entity::job_run::Column::StartedAt.lt(started_before));
+    }
+
+    let page_size = query.page_size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE);
+    let paginator = select
+        .order_by_desc(entity::job_run::Column::Id)
+        .paginate(&app_state.mysql, page_size);
+
+    let total = paginator.num_items().await?;
+    // 页码从1开始
+    let list = paginator.fetch_page(query.page.unwrap_or(1).saturating_sub(1)).await?;
+
+    success_ret(ListRes { list, total })
+}
+
+/// 查询单次运行的状态
+#[get("tasks/runs/{id}")]
+pub async fn run_status(app_state: web::Data<AppState>, id: web::Path<i32>) -> HttpResult {
+    let Some(run) = entity::job_run::Entity::find_by_id(id.into_inner())
+        .one(&app_state.mysql)
+        .await? else {
+        return fail_ret("运行记录不存在");
+    };
+
+    success_ret(run)
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use actix_web::{get, post, web};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppState, HttpResult,
+    api::admin::{
+        middleware::RequireRole,
+        role::{LoginUser, Role},
+    },
+    util::{
+        password::hash_password,
+        res::{fail_ret, success_ret},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUserReq {
+    username: String,
+    password: String,
+    role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleReq {
+    role: String,
+}
+
+/// 当前登录的账号
+#[get("me")]
+pub async fn me(app_state: web::Data<AppState>, login_user: web::ReqData<LoginUser>) -> HttpResult {
+    let user = entity::admin_user::Entity::find_by_id(login_user.id)
+        .one(&app_state.mysql)
+        .await?;
+
+    success_ret(user)
+}
+
+#[get("users", wrap = "RequireRole(Role::Admin)")]
+pub async fn list_users(app_state: web::Data<AppState>) -> HttpResult {
+    let users = entity::admin_user::Entity::find()
+        .order_by_asc(entity::admin_user::Column::Id)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(users)
+}
+
+#[post("users", wrap = "RequireRole(Role::Admin)")]
+pub async fn create_user(app_state: web::Data<AppState>, req: web::Json<CreateUserReq>) -> HttpResult {
+    let Ok(role) = Role::from_str(&req.role) else {
+        return fail_ret("角色不存在");
+    };
+
+    if req.username.is_empty() || req.password.is_empty() {
+        return fail_ret("用户名和密码不能为空");
+    }
+
+    let exists = entity::admin_user::Entity::find()
+        .filter(entity::admin_user::Column::Username.eq(&req.username))
+        .one(&app_state.mysql)
+        .await?;
+    if exists.is_some() {
+        return fail_ret("用户名已存在");
+    }
+
+    let new_user = entity::admin_user::ActiveModel {
+        id: NotSet,
+        username: Set(req.username.clone()),
+        password_hash: Set(hash_password(&req.password)?),
+        role: Set(role.as_str().to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+    let user = new_user.insert(&app_state.mysql).await?;
+
+    success_ret(user)
+}
+
+#[post("users/{id}/role", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_role(
+    app_state: web::Data<AppState>,
+    login_user: web::ReqData<LoginUser>,
+    id: web::Path<i32>,
+    req: web::Json<UpdateRoleReq>,
+) -> HttpResult {
+    let Ok(role) = Role::from_str(&req.role) else {
+        return fail_ret("角色不存在");
+    };
+
+    let id = id.into_inner();
+    // 防止把自己降级后没有管理员
+    if id == login_user.id && role != Role::Admin {
+        return fail_ret("不能修改自己的角色");
+    }
+
+    let Some(user) = entity::admin_user::Entity::find_by_id(id)
+        .one(&app_state.mysql)
+        .await? else {
+        return fail_ret("用户不存在");
+    };
+
+    let mut user = user.into_active_model();
+    user.role = Set(role.as_str().to_string());
+    let user = user.update(&app_state.mysql).await?;
+
+    success_ret(user)
+}
@@ -0,0 +1,205 @@
+use std::{env, str::FromStr};
+
+use actix_web::{HttpResponse, get, web};
+use atom_syndication::{ContentBuilder, EntryBuilder, FeedBuilder, FixedDateTime, LinkBuilder};
+use chrono::{DateTime, Utc};
+use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::{AppState, HttpResult, bots::publisher::SummaryKind};
+
+// 每个订阅源返回的最大条目数
+const FEED_ENTRY_LIMIT: u64 = 50;
+
+enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+#[get("feed.atom")]
+pub async fn atom_feed(app_state: web::Data<AppState>) -> HttpResult {
+    render_feed(&app_state, None, FeedFormat::Atom).await
+}
+
+#[get("feed.rss")]
+pub async fn rss_feed(app_state: web::Data<AppState>) -> HttpResult {
+    render_feed(&app_state, None, FeedFormat::Rss).await
+}
+
+#[get("feed/{kind}.atom")]
+pub async fn kind_atom_feed(app_state: web::Data<AppState>, kind: web::Path<String>) -> HttpResult {
+    let Ok(kind) = SummaryKind::from_str(&kind) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    render_feed(&app_state, Some(kind), FeedFormat::Atom).await
+}
+
+#[get("feed/{kind}.rss")]
+pub async fn kind_rss_feed(app_state: web::Data<AppState>, kind: web::Path<String>) -> HttpResult {
+    let Ok(kind) = SummaryKind::from_str(&kind) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    render_feed(&app_state, Some(kind), FeedFormat::Rss).await
+}
+
+async fn render_feed(app_state: &AppState, kind: Option<SummaryKind>, format: FeedFormat) -> HttpResult {
+    let mut query = entity::feed_entry::Entity::find();
+    if let Some(kind) = kind {
+        query = query.filter(entity::feed_entry::Column::Kind.eq(kind.as_str()));
+    }
+
+    let entries = query
+        .order_by_desc(entity::feed_entry::Column::CreatedAt)
+        .limit(FEED_ENTRY_LIMIT)
+        .all(&app_state.mysql)
+        .await?;
+
+    let site_url = env::var("FEED_SITE_URL").unwrap_or("http://127.0.0.1:15698".to_string());
+
+    let res = match format {
+        FeedFormat::Atom => HttpResponse::Ok()
+            .content_type("application/atom+xml; charset=utf-8")
+            .body(build_atom(&site_url, kind, &entries)),
+        FeedFormat::Rss => HttpResponse::Ok()
+            .content_type("application/rss+xml; charset=utf-8")
+            .body(build_rss(&site_url, kind, &entries)),
+    };
+
+    Ok(res)
+}
+
+fn feed_title(kind: Option<SummaryKind>) -> String {
+    match kind {
+        Some(kind) => format!("BevyBot {} 总结", kind.as_str()),
+        None => "BevyBot 总结".to_string(),
+    }
+}
+
+fn feed_url(site_url: &str, kind: Option<SummaryKind>, ext: &str) -> String {
+    let site_url = site_url.trim_end_matches('/');
+    match kind {
+        Some(kind) => format!("{}/api/client/feed/{}.{}", site_url, kind.as_str(), ext),
+        None => format!("{}/api/client/feed.{}", site_url, ext),
+    }
+}
+
+fn entry_links(entry: &entity::feed_entry::Model) -> Vec<String> {
+    serde_json::from_str(&entry.links).unwrap_or_default()
+}
+
+fn entry_id(site_url: &str, entry: &entity::feed_entry::Model) -> String {
+    format!("{}/api/client/feed/entry/{}", site_url.trim_end_matches('/'), entry.id)
+}
+
+fn entry_time(entry: &entity::feed_entry::Model) -> FixedDateTime {
+    DateTime::<Utc>::from_naive_utc_and_offset(entry.created_at, Utc).fixed_offset()
+}
+
+fn build_atom(site_url: &str, kind: Option<SummaryKind>, entries: &[entity::feed_entry::Model]) -> String {
+    let self_url = feed_url(site_url, kind, "atom");
+
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let links = entry_links(entry)
+                .into_iter()
+                .map(|link| LinkBuilder::default().href(link).rel("related").build())
+                .collect::<Vec<_>>();
+
+            EntryBuilder::default()
+                .id(entry_id(site_url, entry))
+                .title(entry.title.clone())
+                .updated(entry_time(entry))
+                .published(Some(entry_time(entry)))
+                .links(links)
+                .content(Some(
+                    ContentBuilder::default()
+                        .value(Some(entry.body.clone()))
+                        .content_type(Some("text".to_string()))
+                        .build()
+                ))
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    let updated = entries
+        .first()
+        .map(entry_time)
+        .unwrap_or_else(|| Utc::now().fixed_offset());
+
+    FeedBuilder::default()
+        .id(self_url.clone())
+        .title(feed_title(kind))
+        .updated(updated)
+        .links(vec![LinkBuilder::default().href(self_url).rel("self").build()])
+        .entries(items)
+        .build()
+        .to_string()
+}
+
+fn build_rss(site_url: &str, kind: Option<SummaryKind>, entries: &[entity::feed_entry::Model]) -> String {
+    let items = entries
+        .iter()
+        .map(|entry| {
+            ItemBuilder::default()
+                .title(Some(entry.title.clone()))
+                .link(entry_links(entry).into_iter().next())
+                .description(Some(entry.body.clone()))
+                .guid(Some(
+                    GuidBuilder::default()
+                        .value(entry_id(site_url, entry))
+                        .permalink(false)
+                        .build()
+                ))
+                .pub_date(Some(entry_time(entry).to_rfc2822()))
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    ChannelBuilder::default()
+        .title(feed_title(kind))
+        .link(feed_url(site_url, kind, "rss"))
+        .description(feed_title(kind))
+        .items(items)
+        .build()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{api::client::feed::{build_atom, build_rss}, bots::publisher::SummaryKind};
+
+    fn fixture() -> Vec<entity::feed_entry::Model> {
+        vec![entity::feed_entry::Model {
+            id: 1,
+            kind: "issues".to_string(),
+            title: "每日 Issues 总结：2025-11-10".to_string(),
+            body: "## 总结 <b>&</b>".to_string(),
+            links: r#"["https://github.com/bevyengine/bevy/issues/1"]"#.to_string(),
+            created_at: NaiveDate::from_ymd_opt(2025, 11, 10).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+        }]
+    }
+
+    #[test]
+    fn test_build_atom() {
+        let xml = build_atom("https://example.com/", Some(SummaryKind::Issues), &fixture());
+
+        assert!(xml.contains("<id>https://example.com/api/client/feed/issues.atom</id>"));
+        assert!(xml.contains("<title>每日 Issues 总结：2025-11-10</title>"));
+        assert!(xml.contains("https://github.com/bevyengine/bevy/issues/1"));
+        assert!(xml.contains("## 总结 &lt;b&gt;&amp;&lt;/b&gt;"));
+        assert!(xml.contains("2025-11-10T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn test_build_rss() {
+        let xml = build_rss("https://example.com", None, &fixture());
+
+        assert!(xml.contains("<link>https://example.com/api/client/feed.rss</link>"));
+        assert!(xml.contains("<link>https://github.com/bevyengine/bevy/issues/1</link>"));
+        assert!(xml.contains("https://example.com/api/client/feed/entry/1"));
+        assert!(xml.contains("Mon, 10 Nov 2025 12:00:00 +0000"));
+    }
+}
@@ -0,0 +1,31 @@
+use actix_web::{get, web};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    AppState, HttpResult,
+    util::res::{fail_ret, success_ret},
+};
+
+/// 已生成的迁移指南，最新的在前
+#[get("migration-guides")]
+pub async fn list_migration_guides(app_state: web::Data<AppState>) -> HttpResult {
+    let list = entity::migration_guide::Entity::find()
+        .order_by_desc(entity::migration_guide::Column::Id)
+        .all(&app_state.mysql)
+        .await?;
+
+    success_ret(list)
+}
+
+/// 按里程碑名称查询迁移指南，例如 `0.18`
+#[get("migration-guides/{milestone}")]
+pub async fn get_migration_guide(app_state: web::Data<AppState>, milestone: web::Path<String>) -> HttpResult {
+    let Some(guide) = entity::migration_guide::Entity::find()
+        .filter(entity::migration_guide::Column::Milestone.eq(milestone.into_inner()))
+        .one(&app_state.mysql)
+        .await? else {
+        return fail_ret("迁移指南不存在");
+    };
+
+    success_ret(guide)
+}
@@ -1,5 +1,15 @@
 use actix_web::{Scope, web};
+mod feed;
+mod migration_guide;
+mod webhook;
 
 pub fn client() -> Scope {
     web::scope("client")
-}
\ No newline at end of file
+        .service(feed::atom_feed)
+        .service(feed::rss_feed)
+        .service(feed::kind_atom_feed)
+        .service(feed::kind_rss_feed)
+        .service(webhook::github_webhook)
+        .service(migration_guide::list_migration_guides)
+        .service(migration_guide::get_migration_guide)
+}
@@ -0,0 +1,148 @@
+use std::env;
+
+use actix_web::{HttpRequest, HttpResponse, post, web};
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use sha2::Sha256;
+
+use crate::{
+    AppState, HttpResult,
+    tasks::github_task::{
+        repo::enabled_repo_names,
+        webhook::{delivery_key, route_event, spawn_action, urgent_labels},
+    },
+    util::{cache, res::success_ret},
+};
+
+// GitHub 不会在一天后重新投递同一个事件
+const DELIVERY_TTL_SEC: u64 = 24 * 3600;
+
+/// 接收 GitHub Webhook，校验签名并按投递ID去重后在后台处理
+#[post("github/webhook")]
+pub async fn github_webhook(
+    app_state: web::Data<AppState>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> HttpResult {
+    let Ok(secret) = env::var("GITHUB_WEBHOOK_SECRET") else {
+        warn!("未配置 GITHUB_WEBHOOK_SECRET，拒绝 Webhook 请求");
+        return Ok(HttpResponse::Unauthorized().finish());
+    };
+
+    let signature = get_header(&request, "X-Hub-Signature-256");
+    if !verify_signature(&secret, &body, signature) {
+        warn!("Webhook 签名校验失败");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let (Some(event), Some(delivery_id)) = (
+        get_header(&request, "X-GitHub-Event"),
+        get_header(&request, "X-GitHub-Delivery"),
+    ) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let watched_repos = enabled_repo_names(&app_state).await?;
+    let Some(event_action) = route_event(event, &body, &urgent_labels(), &watched_repos)? else {
+        return success_ret("ignored");
+    };
+
+    if !cache::put_nx_ttl(&app_state.redis, &delivery_key(delivery_id), event, DELIVERY_TTL_SEC).await? {
+        info!("重复的 Webhook 投递: {}", delivery_id);
+        return success_ret("duplicate");
+    }
+
+    info!("收到 Webhook 事件: {}, 投递ID: {}, 处理: {:?}", event, delivery_id, event_action);
+    spawn_action(app_state.get_ref().clone(), event_action, delivery_id.to_string());
+
+    success_ret("accepted")
+}
+
+fn get_header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+/// 校验 `X-Hub-Signature-256: sha256=<hex>`
+pub fn verify_signature(secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(signature) = signature.and_then(|signature| signature.strip_prefix("sha256=")) else {
+        return false;
+    };
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+
+    mac.update(body);
+    // 常数时间比较
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, http::StatusCode, test::{TestRequest, call_and_read_body_json, call_service, init_service}, web};
+    use dotenvy::dotenv;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    use crate::{AppState, api::client::webhook::{github_webhook, verify_signature}};
+
+    const MILESTONE_CLOSED: &str = include_str!("../../../tests/fixtures/webhook/milestone_closed.json");
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = b"Hello, World!";
+        // GitHub 文档中的示例
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        assert!(verify_signature("It's a Secret to Everybody", body, Some(signature)));
+
+        assert_eq!(sign("It's a Secret to Everybody", body), signature);
+        assert!(!verify_signature("wrong secret", body, Some(signature)));
+        assert!(!verify_signature("It's a Secret to Everybody", b"Hello", Some(signature)));
+        assert!(!verify_signature("It's a Secret to Everybody", body, Some("sha256=zz")));
+        assert!(!verify_signature("It's a Secret to Everybody", body, None));
+    }
+
+    /// 本地投递录制的事件，需要 Redis 和数据库
+    #[actix_web::test]
+    async fn test_post_recorded_payload() {
+        dotenv().ok();
+        let secret = std::env::var("GITHUB_WEBHOOK_SECRET").unwrap();
+
+        let app_state = AppState::new_with_default().await;
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(app_state))
+                .service(github_webhook)
+        ).await;
+
+        let delivery_id = format!("test-{}", chrono::Utc::now().timestamp_millis());
+        let request = |signature: String| {
+            TestRequest::post()
+                .uri("/github/webhook")
+                .insert_header(("X-GitHub-Event", "milestone"))
+                .insert_header(("X-GitHub-Delivery", delivery_id.as_str()))
+                .insert_header(("X-Hub-Signature-256", signature))
+                .set_payload(MILESTONE_CLOSED)
+                .to_request()
+        };
+
+        let res = call_service(&app, request("sha256=00".to_string())).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let signature = sign(&secret, MILESTONE_CLOSED.as_bytes());
+        let res: serde_json::Value = call_and_read_body_json(&app, request(signature.clone())).await;
+        assert_eq!(res["data"], "accepted");
+
+        // 同一个投递ID只处理一次
+        let res: serde_json::Value = call_and_read_body_json(&app, request(signature)).await;
+        assert_eq!(res["data"], "duplicate");
+    }
+}
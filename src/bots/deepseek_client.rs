@@ -1,7 +1,15 @@
 use anyhow::Result;
-use deepseek_api::{DeepSeekClient, DeepSeekClientBuilder};
+use deepseek_api::{
+    CompletionsRequestBuilder, DeepSeekClient, DeepSeekClientBuilder, RequestBuilder,
+    request::{MessageRequest, SystemMessageRequest},
+    response::{AssistantMessage, ChatCompletion, ChatCompletionStream, ChatResponse, JSONChoiceStream, ModelType},
+};
+use log::info;
 
-use crate::bots::REQUEST_TIME_OUT_SEC;
+use crate::bots::{
+    REQUEST_TIME_OUT_SEC,
+    llm_client::{ChatMessage, ChatRole, LlmOutput, LlmProvider, LlmUsage, chunk_budget},
+};
 
 pub fn build_deepseek_client() -> Result<DeepSeekClient> {
     let deepseek_api_key = std::env::var("DEEPSEEK_API_KEY")?;
@@ -9,3 +17,101 @@ pub fn build_deepseek_client() -> Result<DeepSeekClient> {
 
     Ok(deepseek_client)
 }
+
+/// DeepSeek 后端，模型为空时从 `DEEPSEEK_MODEL` 读取，默认 `deepseek-reasoner`
+pub struct DeepSeekProvider {
+    client: DeepSeekClient,
+    name: String,
+    model: ModelType,
+    chunk_tokens: usize,
+}
+
+impl DeepSeekProvider {
+    pub fn from_env(model: Option<&str>) -> Result<Self> {
+        let model = match model {
+            Some(model) => model.to_string(),
+            None => std::env::var("DEEPSEEK_MODEL").unwrap_or_default(),
+        };
+        let model = match model.as_str() {
+            "deepseek-chat" => ModelType::DeepSeekChat,
+            "deepseek-reasoner" | "" => ModelType::DeepSeekReasoner,
+            model => anyhow::bail!("不支持的 DeepSeek 模型: {}", model),
+        };
+
+        Ok(Self {
+            client: build_deepseek_client()?,
+            name: format!("deepseek:{}", model_name(&model)),
+            chunk_tokens: chunk_budget(model_name(&model)),
+            model,
+        })
+    }
+}
+
+fn model_name(model: &ModelType) -> &'static str {
+    match model {
+        ModelType::DeepSeekChat => "deepseek-chat",
+        ModelType::DeepSeekReasoner => "deepseek-reasoner",
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for DeepSeekProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chunk_tokens(&self) -> usize {
+        self.chunk_tokens
+    }
+
+    async fn chat(&self, messages: &[ChatMessage], max_tokens: Option<u32>) -> Result<LlmOutput> {
+        let messages = messages
+            .iter()
+            .map(|message| match message.role {
+                ChatRole::System => MessageRequest::System(SystemMessageRequest::new(&message.content)),
+                ChatRole::User => MessageRequest::user(&message.content),
+                ChatRole::Assistant => MessageRequest::Assistant(AssistantMessage::new(&message.content)),
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder = CompletionsRequestBuilder::new(&messages)
+            .use_model(self.model.clone())
+            .stream(false);
+        if let Some(max_tokens) = max_tokens {
+            builder = builder.max_tokens(max_tokens)?;
+        }
+        let res = builder.do_request(&self.client).await?;
+
+        get_first_deepseek_response(res)
+    }
+}
+
+fn get_first_deepseek_response(
+    response: ChatResponse<ChatCompletion, ChatCompletionStream<JSONChoiceStream>>
+) -> Result<LlmOutput> {
+
+    let response = response.must_response();
+    info!("{:?}", response);
+
+    if let Some(choice) = response.choices.first() {
+        if let Some(message) = &choice.message {
+            if !message.content.is_empty() {
+                Ok(LlmOutput {
+                    text: message.content.clone(),
+                    model: response.model.clone(),
+                    usage: LlmUsage {
+                        prompt_tokens: response.usage.prompt_tokens,
+                        completion_tokens: response.usage.completion_tokens,
+                        total_tokens: response.usage.total_tokens,
+                    },
+                })
+            } else {
+                anyhow::bail!("文本为空");
+            }
+        } else {
+            anyhow::bail!("获取text失败");
+        }
+    } else {
+        anyhow::bail!("获取choices失败");
+    }
+}
@@ -0,0 +1,209 @@
+use std::{collections::HashMap, env, time::Duration};
+
+use anyhow::Result;
+use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::bots::{
+    REQUEST_TIME_OUT_SEC,
+    publisher::{Publisher, Summary, SummaryKind, split_text},
+};
+
+// Discord 的消息长度限制
+const MESSAGE_MAX_CHARS: usize = 2000;
+const EMBED_TITLE_MAX_CHARS: usize = 256;
+const EMBED_DESCRIPTION_MAX_CHARS: usize = 4096;
+
+#[derive(Debug, Serialize)]
+struct WebhookMessage {
+    content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    embeds: Vec<Embed>,
+}
+
+#[derive(Debug, Serialize)]
+struct Embed {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookMessageRes {
+    id: String,
+}
+
+/// Discord Webhook 发布渠道
+pub struct DiscordWebhookPublisher {
+    client: Client,
+    /// 没有按类型配置时使用的 Webhook
+    webhook_url: Option<String>,
+    /// 仓库配置的 Webhook，优先于 `webhook_url`
+    webhook_urls: HashMap<SummaryKind, String>,
+}
+
+impl DiscordWebhookPublisher {
+    pub fn new() -> Result<Self> {
+        let webhook_url = env::var("DISCORD_WEBHOOK_URL")?;
+        Self::with_webhook_url(&webhook_url)
+    }
+
+    pub fn with_webhook_url(webhook_url: &str) -> Result<Self> {
+        Ok(Self {
+            client: build_client()?,
+            webhook_url: Some(webhook_url.to_string()),
+            webhook_urls: HashMap::new(),
+        })
+    }
+
+    /// 只发布到仓库配置的 Webhook，不使用环境变量
+    pub fn for_channels(webhook_urls: &HashMap<SummaryKind, String>) -> Result<Self> {
+        Ok(Self {
+            client: build_client()?,
+            webhook_url: None,
+            webhook_urls: webhook_urls.clone(),
+        })
+    }
+
+    pub fn with_channels(mut self, webhook_urls: &HashMap<SummaryKind, String>) -> Self {
+        self.webhook_urls.extend(webhook_urls.iter().map(|(kind, url)| (*kind, url.clone())));
+        self
+    }
+
+    /// 发送一条消息，返回消息ID
+    async fn execute(&self, webhook_url: &str, message: &WebhookMessage) -> Result<String> {
+        let res = self.client.post(webhook_url)
+            // wait=true 时 Discord 会返回创建的消息
+            .query(&[("wait", "true")])
+            .json(message)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let data: WebhookMessageRes = res.json().await?;
+
+        Ok(data.id)
+    }
+}
+
+fn build_client() -> Result<Client> {
+    Ok(ClientBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIME_OUT_SEC))
+        .build()?)
+}
+
+/// 标题和原文链接放在 embed 中
+fn build_embed(summary: &Summary) -> Embed {
+    let mut description = String::new();
+    for link in &summary.links {
+        let line = format!("{}\n", link);
+        if description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_MAX_CHARS {
+            break;
+        }
+        description.push_str(&line);
+    }
+
+    Embed {
+        title: summary.title.chars().take(EMBED_TITLE_MAX_CHARS).collect(),
+        url: summary.links.first().cloned(),
+        description,
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for DiscordWebhookPublisher {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn publish(&self, summary: &Summary) -> Result<Option<String>> {
+        let Some(webhook_url) = self.webhook_urls.get(&summary.kind).or(self.webhook_url.as_ref()) else {
+            anyhow::bail!("未配置 {} 的 Discord Webhook", summary.kind.as_str());
+        };
+
+        let mut embeds = vec![build_embed(summary)];
+        let mut first_id = None;
+
+        let mut chunks = split_text(&summary.text, MESSAGE_MAX_CHARS);
+        if chunks.is_empty() {
+            chunks.push(String::new());
+        }
+
+        for content in chunks {
+            let id = self.execute(webhook_url, &WebhookMessage {
+                content,
+                // 只有第一条消息带 embed
+                embeds: std::mem::take(&mut embeds),
+            }).await?;
+
+            first_id.get_or_insert(id);
+        }
+
+        Ok(first_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use actix_web::{App, HttpResponse, HttpServer, post, web};
+    use serde_json::json;
+
+    use crate::bots::{
+        discord_client::DiscordWebhookPublisher,
+        publisher::{Publisher, Summary, SummaryKind},
+    };
+
+    type Received = web::Data<Mutex<Vec<serde_json::Value>>>;
+
+    #[post("/webhook")]
+    async fn webhook(received: Received, body: web::Json<serde_json::Value>) -> HttpResponse {
+        let mut received = received.lock().unwrap();
+        received.push(body.into_inner());
+        HttpResponse::Ok().json(json!({ "id": received.len().to_string() }))
+    }
+
+    #[actix_web::test]
+    async fn test_publish_to_webhook() {
+        let received: Received = web::Data::new(Mutex::new(Vec::new()));
+
+        let app_data = received.clone();
+        let server = HttpServer::new(move || App::new().app_data(app_data.clone()).service(webhook))
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let publisher = DiscordWebhookPublisher::with_webhook_url(&format!("http://{}/webhook", addr)).unwrap();
+
+        let text = "第一行\n".repeat(600);
+        let summary = Summary::new(SummaryKind::Prs, "每日 PRs 总结：2025-11-10", &text)
+            .with_links(vec!["https://github.com/bevyengine/bevy/pull/1".to_string()]);
+
+        let id = publisher.publish(&summary).await.unwrap();
+        assert_eq!(id, Some("1".to_string()));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0]["embeds"][0]["title"], summary.title);
+        assert_eq!(received[0]["embeds"][0]["url"], "https://github.com/bevyengine/bevy/pull/1");
+        assert!(received[1].get("embeds").is_none());
+        for message in received.iter() {
+            assert!(message["content"].as_str().unwrap().chars().count() <= 2000);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_publish_without_webhook() {
+        let webhook_urls = HashMap::from([(SummaryKind::Issues, "http://127.0.0.1:1/webhook".to_string())]);
+        let publisher = DiscordWebhookPublisher::for_channels(&webhook_urls).unwrap();
+
+        // 没有配置该类型的 Webhook，也没有默认 Webhook
+        let summary = Summary::new(SummaryKind::Prs, "每日 PRs 总结：2025-11-10", "内容");
+        let err = publisher.publish(&summary).await.unwrap_err();
+        assert!(err.to_string().contains("prs"));
+    }
+}
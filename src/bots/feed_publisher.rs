@@ -0,0 +1,42 @@
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, DatabaseConnection};
+
+use crate::bots::publisher::{Publisher, Summary};
+
+/// 保存到数据库，供 `/api/client/feed.atom` 和 `/api/client/feed.rss` 订阅
+pub struct FeedPublisher {
+    mysql: DatabaseConnection,
+}
+
+impl FeedPublisher {
+    pub fn new(mysql: DatabaseConnection) -> Self {
+        Self { mysql }
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for FeedPublisher {
+    fn name(&self) -> &str {
+        "feed"
+    }
+
+    async fn publish(&self, summary: &Summary) -> Result<Option<String>> {
+        let new_entry = entity::feed_entry::ActiveModel {
+            id: NotSet,
+            kind: Set(summary.kind.as_str().to_string()),
+            title: Set(summary.title.clone()),
+            body: Set(summary.text.clone()),
+            links: Set(serde_json::to_string(&summary.links)?),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        let entry = new_entry.insert(&self.mysql).await?;
+
+        Ok(Some(entry.id.to_string()))
+    }
+
+    fn is_external(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,339 @@
+use std::{env, time::Duration};
+
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::bots::{deepseek_client::DeepSeekProvider, openai_client::OpenAiCompatibleProvider};
+
+/// 未配置 `LLM_PROVIDER` 时使用的后端
+pub const DEFAULT_PROVIDER: &str = "deepseek";
+// 每个后端单次请求的超时时间，推理模型较慢
+const DEFAULT_ATTEMPT_TIMEOUT_SEC: u64 = 60 * 10;
+// 未知模型单次请求的输入预算，按较小的本地模型估计
+const DEFAULT_CHUNK_TOKENS: usize = 6000;
+// DeepSeek 上下文为64K，留出输出和提示词的空间
+const DEEPSEEK_CHUNK_TOKENS: usize = 40000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+/// 发送给 AI 的一条消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: &str) -> Self {
+        Self { role: ChatRole::System, content: content.to_string() }
+    }
+
+    pub fn user(content: &str) -> Self {
+        Self { role: ChatRole::User, content: content.to_string() }
+    }
+}
+
+/// Token 用量，部分本地模型不返回时为0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+/// AI 返回的文本、实际使用的模型和用量
+#[derive(Debug, Clone)]
+pub struct LlmOutput {
+    pub text: String,
+    pub model: String,
+    pub usage: LlmUsage,
+}
+
+/// AI 后端，输入对话消息，输出文本和用量
+#[async_trait::async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// 后端名称，用于日志
+    fn name(&self) -> &str;
+
+    /// 单次请求中输入内容的 Token 预算，超出时需要分批总结
+    fn chunk_tokens(&self) -> usize;
+
+    /// `max_tokens` 为空时使用后端的默认值
+    async fn chat(&self, messages: &[ChatMessage], max_tokens: Option<u32>) -> Result<LlmOutput>;
+}
+
+/// 后端链中的一项，格式为 `名称[:模型]`，例如 `deepseek:deepseek-chat`、`ollama:qwen2.5:7b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderEntry {
+    pub name: String,
+    /// 为空时使用后端配置中的模型
+    pub model: Option<String>,
+}
+
+impl ProviderEntry {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        let (name, model) = match value.split_once(':') {
+            Some((name, model)) => (name.trim(), Some(model.trim().to_string()).filter(|model| !model.is_empty())),
+            None => (value, None),
+        };
+
+        Some(Self { name: name.to_lowercase(), model })
+    }
+
+    fn build(&self) -> Result<Box<dyn LlmProvider>> {
+        if self.name == DEFAULT_PROVIDER {
+            return Ok(Box::new(DeepSeekProvider::from_env(self.model.as_deref())?));
+        }
+
+        Ok(Box::new(OpenAiCompatibleProvider::from_env(&self.name, self.model.as_deref())?))
+    }
+}
+
+/// 按顺序尝试多个后端，前一个失败或超时后使用下一个
+pub struct FallbackProvider {
+    name: String,
+    providers: Vec<Box<dyn LlmProvider>>,
+    attempt_timeout: Duration,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Box<dyn LlmProvider>>, attempt_timeout: Duration) -> Self {
+        let name = providers
+            .iter()
+            .map(|provider| provider.name())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        Self { name, providers, attempt_timeout }
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 降级后的后端也要放得下，取最小值
+    fn chunk_tokens(&self) -> usize {
+        self.providers
+            .iter()
+            .map(|provider| provider.chunk_tokens())
+            .min()
+            .unwrap_or(DEFAULT_CHUNK_TOKENS)
+    }
+
+    async fn chat(&self, messages: &[ChatMessage], max_tokens: Option<u32>) -> Result<LlmOutput> {
+        let mut errors = vec![];
+
+        for provider in &self.providers {
+            match tokio::time::timeout(self.attempt_timeout, provider.chat(messages, max_tokens)).await {
+                Ok(Ok(output)) => {
+                    if !errors.is_empty() {
+                        warn!("AI 后端降级到 {}, 模型: {}", provider.name(), output.model);
+                    }
+                    return Ok(output);
+                }
+                Ok(Err(err)) => {
+                    warn!("AI 后端 {} 请求失败: {err:?}", provider.name());
+                    errors.push(format!("{}: {}", provider.name(), err));
+                }
+                Err(_) => {
+                    warn!("AI 后端 {} 请求超时: {}秒", provider.name(), self.attempt_timeout.as_secs());
+                    errors.push(format!("{}: 请求超时", provider.name()));
+                }
+            }
+        }
+
+        anyhow::bail!("所有 AI 后端都请求失败: {}", errors.join("; "))
+    }
+}
+
+/// 按任务选择 AI 后端
+///
+/// 先读取 `LLM_PROVIDER_<任务>`（例如 `LLM_PROVIDER_MERGE_TRAIN`），再读取 `LLM_PROVIDER`，默认 `deepseek`。
+/// 可以用逗号分隔多个后端，按顺序降级，每次尝试的超时时间从 `LLM_ATTEMPT_TIMEOUT_SEC` 读取。
+/// 除 `deepseek` 以外的名称都按 OpenAI 兼容接口处理，从 `LLM_<名称>_BASE_URL`、`LLM_<名称>_API_KEY`、`LLM_<名称>_MODEL` 读取配置
+pub fn build_llm_provider(task: &str) -> Result<Box<dyn LlmProvider>> {
+    let providers = provider_chain(task)
+        .iter()
+        .map(ProviderEntry::build)
+        .collect::<Result<Vec<_>>>()?;
+
+    let attempt_timeout = env::var("LLM_ATTEMPT_TIMEOUT_SEC")
+        .ok()
+        .and_then(|timeout| timeout.parse::<u64>().ok())
+        .filter(|timeout| *timeout > 0)
+        .unwrap_or(DEFAULT_ATTEMPT_TIMEOUT_SEC);
+
+    Ok(Box::new(FallbackProvider::new(providers, Duration::from_secs(attempt_timeout))))
+}
+
+/// 任务使用的后端链，没有配置时只使用 `deepseek`
+pub fn provider_chain(task: &str) -> Vec<ProviderEntry> {
+    let value = env::var(format!("LLM_PROVIDER_{}", env_key(task)))
+        .ok()
+        .filter(|provider| !provider.trim().is_empty())
+        .or_else(|| env::var("LLM_PROVIDER").ok())
+        .unwrap_or_default();
+
+    parse_chain(&value)
+}
+
+/// 解析逗号分隔的后端链，为空时只使用 `deepseek`
+pub fn parse_chain(value: &str) -> Vec<ProviderEntry> {
+    let chain = value.split(',').filter_map(ProviderEntry::parse).collect::<Vec<_>>();
+    if chain.is_empty() {
+        return vec![ProviderEntry { name: DEFAULT_PROVIDER.to_string(), model: None }];
+    }
+
+    chain
+}
+
+/// 模型单次请求的输入预算
+///
+/// 先读取 `LLM_CHUNK_TOKENS_<模型>`（例如 `LLM_CHUNK_TOKENS_DEEPSEEK_CHAT`），再读取 `LLM_CHUNK_TOKENS`，
+/// 都没有配置时 DeepSeek 为40000，其他模型为6000
+pub fn chunk_budget(model: &str) -> usize {
+    let configured = env::var(format!("LLM_CHUNK_TOKENS_{}", env_key(model)))
+        .or_else(|_| env::var("LLM_CHUNK_TOKENS"))
+        .ok()
+        .and_then(|tokens| tokens.parse::<usize>().ok())
+        .filter(|tokens| *tokens > 0);
+
+    configured.unwrap_or(if model.starts_with("deepseek-") { DEEPSEEK_CHUNK_TOKENS } else { DEFAULT_CHUNK_TOKENS })
+}
+
+/// 估算文本的 Token 数，英文约4个字符一个 Token，中文按每个字一个 Token 偏大估计
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(char::is_ascii).count();
+    let other = text.chars().count() - ascii;
+
+    ascii.div_ceil(4) + other
+}
+
+/// 环境变量名中使用的部分，例如 `merge-train` -> `MERGE_TRAIN`
+pub fn env_key(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+
+    use crate::bots::llm_client::{
+        ChatMessage, FallbackProvider, LlmOutput, LlmProvider, LlmUsage, ProviderEntry, env_key, estimate_tokens,
+        parse_chain,
+    };
+
+    // 模拟后端：`delay` 后返回结果，`fail` 为真时返回错误
+    struct MockProvider {
+        model: &'static str,
+        delay: Duration,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for MockProvider {
+        fn name(&self) -> &str {
+            self.model
+        }
+
+        fn chunk_tokens(&self) -> usize {
+            self.delay.as_millis() as usize + 1000
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage], _max_tokens: Option<u32>) -> Result<LlmOutput> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                anyhow::bail!("服务繁忙");
+            }
+
+            Ok(LlmOutput {
+                text: "总结".to_string(),
+                model: self.model.to_string(),
+                usage: LlmUsage::default(),
+            })
+        }
+    }
+
+    fn mock(model: &'static str, delay_ms: u64, fail: bool) -> Box<dyn LlmProvider> {
+        Box::new(MockProvider { model, delay: Duration::from_millis(delay_ms), fail })
+    }
+
+    #[test]
+    fn test_env_key() {
+        assert_eq!(env_key("merge-train"), "MERGE_TRAIN");
+        assert_eq!(env_key("issues"), "ISSUES");
+        assert_eq!(env_key(" qwen.local "), "QWEN_LOCAL");
+    }
+
+    #[test]
+    fn test_message_json() {
+        let message = serde_json::to_value(ChatMessage::system("你好")).unwrap();
+        assert_eq!(message, serde_json::json!({ "role": "system", "content": "你好" }));
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hello world"), 3);
+        assert_eq!(estimate_tokens("内存泄漏 fix"), 5);
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        assert_eq!(
+            parse_chain("deepseek, DeepSeek:deepseek-chat ,,ollama:qwen2.5:7b"),
+            vec![
+                ProviderEntry { name: "deepseek".to_string(), model: None },
+                ProviderEntry { name: "deepseek".to_string(), model: Some("deepseek-chat".to_string()) },
+                ProviderEntry { name: "ollama".to_string(), model: Some("qwen2.5:7b".to_string()) },
+            ]
+        );
+        assert_eq!(parse_chain(" "), vec![ProviderEntry { name: "deepseek".to_string(), model: None }]);
+    }
+
+    #[tokio::test]
+    async fn test_fallback() {
+        let messages = [ChatMessage::user("hello")];
+
+        // 第一个失败，第二个超时，第三个回答
+        let provider = FallbackProvider::new(
+            vec![mock("reasoner", 0, true), mock("chat", 500, false), mock("qwen", 0, false)],
+            Duration::from_millis(100),
+        );
+        assert_eq!(provider.name(), "reasoner -> chat -> qwen");
+        assert_eq!(provider.chunk_tokens(), 1000);
+        assert_eq!(provider.chat(&messages, None).await.unwrap().model, "qwen");
+
+        // 全部失败
+        let provider = FallbackProvider::new(
+            vec![mock("reasoner", 0, true), mock("chat", 500, false)],
+            Duration::from_millis(100),
+        );
+        let err = provider.chat(&messages, None).await.unwrap_err().to_string();
+        assert!(err.contains("reasoner: 服务繁忙"));
+        assert!(err.contains("chat: 请求超时"));
+    }
+}
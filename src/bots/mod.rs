@@ -6,8 +6,15 @@ pub mod qqbot_client;
 pub mod qqbot_github_impl;
 pub mod qqbot_channel_impl;
 pub mod deepseek_client;
+pub mod llm_client;
+pub mod openai_client;
 pub mod github_client;
 pub mod bsky_client;
+pub mod discord_client;
+pub mod feed_publisher;
+pub mod telegram_client;
+pub mod publisher;
+pub mod qqbot_publisher_impl;
 
 
 const REQUEST_TIME_OUT_SEC: u64 = 60 * 30;
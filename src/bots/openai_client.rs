@@ -0,0 +1,217 @@
+use std::{env, time::Duration};
+
+use anyhow::Result;
+use log::info;
+use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::bots::{
+    REQUEST_TIME_OUT_SEC,
+    llm_client::{ChatMessage, LlmOutput, LlmProvider, LlmUsage, chunk_budget, env_key},
+};
+
+#[derive(Debug, Serialize)]
+struct CompletionsReq<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionsRes {
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<LlmUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Option<ChoiceMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChoiceMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// OpenAI 兼容的 Chat Completions 后端，例如通义千问、Moonshot、llama.cpp、Ollama
+pub struct OpenAiCompatibleProvider {
+    client: Client,
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    chunk_tokens: usize,
+}
+
+impl OpenAiCompatibleProvider {
+    /// 从 `LLM_<名称>_BASE_URL`、`LLM_<名称>_API_KEY`、`LLM_<名称>_MODEL` 读取配置，本地模型可以不设置 API Key
+    ///
+    /// `model` 不为空时覆盖配置中的模型
+    pub fn from_env(name: &str, model: Option<&str>) -> Result<Self> {
+        let prefix = format!("LLM_{}", env_key(name));
+
+        let Ok(base_url) = env::var(format!("{}_BASE_URL", prefix)) else {
+            anyhow::bail!("未配置 AI 后端 {} 的 {}_BASE_URL", name, prefix);
+        };
+        let model = match model {
+            Some(model) => model.to_string(),
+            None => {
+                let Ok(model) = env::var(format!("{}_MODEL", prefix)) else {
+                    anyhow::bail!("未配置 AI 后端 {} 的 {}_MODEL", name, prefix);
+                };
+                model
+            }
+        };
+        let api_key = env::var(format!("{}_API_KEY", prefix))
+            .ok()
+            .filter(|api_key| !api_key.is_empty());
+
+        Self::new(name, &base_url, api_key, &model)
+    }
+
+    /// `base_url` 为接口前缀，例如 `https://api.moonshot.cn/v1`，请求时拼接 `/chat/completions`
+    pub fn new(name: &str, base_url: &str, api_key: Option<String>, model: &str) -> Result<Self> {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(REQUEST_TIME_OUT_SEC))
+            .build()?;
+
+        Ok(Self {
+            client,
+            name: format!("{}:{}", name, model),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model: model.to_string(),
+            chunk_tokens: chunk_budget(model),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chunk_tokens(&self) -> usize {
+        self.chunk_tokens
+    }
+
+    async fn chat(&self, messages: &[ChatMessage], max_tokens: Option<u32>) -> Result<LlmOutput> {
+        let mut request = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&CompletionsReq {
+                model: &self.model,
+                messages,
+                stream: false,
+                max_tokens,
+            });
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let res = request.send().await?;
+        let status = res.status();
+        if !status.is_success() {
+            anyhow::bail!("AI 后端 {} 请求失败: {} {}", self.name, status, res.text().await.unwrap_or_default());
+        }
+
+        let data: CompletionsRes = res.json().await?;
+        info!("{:?}", data);
+
+        let Some(choice) = data.choices.first() else {
+            anyhow::bail!("获取choices失败");
+        };
+        let Some(message) = &choice.message else {
+            anyhow::bail!("获取text失败");
+        };
+        let text = message.content.clone().unwrap_or_default();
+        if text.is_empty() {
+            anyhow::bail!("文本为空");
+        }
+
+        Ok(LlmOutput {
+            text,
+            // 部分本地服务不返回模型名称
+            model: if data.model.is_empty() { self.model.clone() } else { data.model },
+            usage: data.usage.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer, post, web};
+    use serde_json::{Value, json};
+
+    use crate::bots::{
+        llm_client::{ChatMessage, LlmProvider, LlmUsage},
+        openai_client::OpenAiCompatibleProvider,
+    };
+
+    // 记录收到的请求头和请求体
+    static RECEIVED: Mutex<Vec<(Option<String>, Value)>> = Mutex::new(Vec::new());
+
+    #[post("/v1/chat/completions")]
+    async fn completions(request: HttpRequest, body: web::Json<Value>) -> HttpResponse {
+        let auth = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        RECEIVED.lock().unwrap().push((auth, body.into_inner()));
+
+        HttpResponse::Ok().json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "model": "qwen-plus",
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": "总结内容" }, "finish_reason": "stop" }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17 }
+        }))
+    }
+
+    #[post("/empty/chat/completions")]
+    async fn empty() -> HttpResponse {
+        HttpResponse::Ok().json(json!({ "choices": [] }))
+    }
+
+    #[actix_web::test]
+    async fn test_chat() {
+        let server = HttpServer::new(|| App::new().service(completions).service(empty))
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let provider = OpenAiCompatibleProvider::new(
+            "qwen",
+            &format!("http://{}/v1/", addr),
+            Some("test-key".to_string()),
+            "qwen-plus",
+        ).unwrap();
+        let messages = [ChatMessage::system("你是翻译"), ChatMessage::user("hello")];
+        let output = provider.chat(&messages, Some(100)).await.unwrap();
+
+        assert_eq!(output.text, "总结内容");
+        assert_eq!(output.model, "qwen-plus");
+        assert_eq!(output.usage, LlmUsage { prompt_tokens: 12, completion_tokens: 5, total_tokens: 17 });
+
+        let (auth, body) = RECEIVED.lock().unwrap().pop().unwrap();
+        assert_eq!(auth.as_deref(), Some("Bearer test-key"));
+        assert_eq!(body["model"], "qwen-plus");
+        assert_eq!(body["max_tokens"], 100);
+        assert_eq!(body["messages"][0], json!({ "role": "system", "content": "你是翻译" }));
+
+        // 没有返回内容
+        let provider = OpenAiCompatibleProvider::new("local", &format!("http://{}/empty", addr), None, "llama").unwrap();
+        assert!(provider.chat(&messages, None).await.is_err());
+    }
+}
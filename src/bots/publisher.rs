@@ -0,0 +1,388 @@
+use std::{env, str::FromStr};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use log::{error, info};
+
+use crate::{
+    AppState,
+    bots::{
+        discord_client::DiscordWebhookPublisher, feed_publisher::FeedPublisher,
+        qqbot_publisher_impl::QQThreadPublisher, telegram_client::TelegramPublisher,
+    },
+    tasks::github_task::repo::{DEFAULT_REPO, GithubRepo},
+};
+
+/// 总结的类型，各发布渠道根据类型选择目标频道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SummaryKind {
+    Issues,
+    Prs,
+    Commits,
+    MergeTrain,
+    Milestone,
+    Release,
+}
+
+impl SummaryKind {
+    pub const ALL: [SummaryKind; 6] = [
+        SummaryKind::Issues,
+        SummaryKind::Prs,
+        SummaryKind::Commits,
+        SummaryKind::MergeTrain,
+        SummaryKind::Milestone,
+        SummaryKind::Release,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryKind::Issues => "issues",
+            SummaryKind::Prs => "prs",
+            SummaryKind::Commits => "commits",
+            SummaryKind::MergeTrain => "merge-train",
+            SummaryKind::Milestone => "milestone",
+            SummaryKind::Release => "release",
+        }
+    }
+}
+
+impl FromStr for SummaryKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SummaryKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("未知的总结类型: {}", s))
+    }
+}
+
+/// 一份待发布的总结
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub kind: SummaryKind,
+    pub title: String,
+    /// AI 生成的 Markdown 文本
+    pub text: String,
+    /// 原文链接
+    pub links: Vec<String>,
+    /// 分组主题，例如里程碑名称
+    pub topic: Option<String>,
+}
+
+impl Summary {
+    pub fn new(kind: SummaryKind, title: &str, text: &str) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            text: text.to_string(),
+            links: Vec::new(),
+            topic: None,
+        }
+    }
+
+    /// 每日总结，标题形如 `每日 Issues 总结：2025-11-10`
+    pub fn daily(kind: SummaryKind, name: &str, date: NaiveDate, text: &str) -> Self {
+        let title = format!("每日 {} 总结：{}", name, date.format("%Y-%m-%d"));
+        Self::new(kind, &title, text)
+    }
+
+    pub fn with_links(mut self, links: Vec<String>) -> Self {
+        self.links = links;
+        self
+    }
+
+    pub fn with_topic(mut self, topic: &str) -> Self {
+        self.topic = Some(topic.to_string());
+        self
+    }
+}
+
+/// 发布渠道
+#[async_trait::async_trait]
+pub trait Publisher: Send + Sync {
+    /// 渠道名称，用于日志和发布结果
+    fn name(&self) -> &str;
+
+    /// 发布总结，返回目标平台的帖子/消息ID（如果有）
+    async fn publish(&self, summary: &Summary) -> Result<Option<String>>;
+
+    /// 是否发布到外部平台，本地存档（feed）不算，只有它成功时不能视为发布成功
+    fn is_external(&self) -> bool {
+        true
+    }
+}
+
+/// 单个渠道的发布结果
+#[derive(Debug)]
+pub struct PublishOutcome {
+    pub publisher: String,
+    pub result: Result<Option<String>>,
+}
+
+/// 一次发布在所有渠道上的结果
+#[derive(Debug, Default)]
+pub struct PublishReport {
+    pub outcomes: Vec<PublishOutcome>,
+}
+
+impl PublishReport {
+    pub fn success_count(&self) -> usize {
+        self.outcomes.iter().filter(|outcome| outcome.result.is_ok()).count()
+    }
+
+    /// 发布到一个渠道并记录结果
+    async fn publish_to(&mut self, sink: &dyn Publisher, summary: &Summary) {
+        let result = sink.publish(summary).await;
+        match &result {
+            Ok(id) => info!("[{}] {} 发布成功: {}, id: {:?}", sink.name(), summary.kind.as_str(), summary.title, id),
+            Err(err) => error!("[{}] {} 发布失败: {}, {err:?}", sink.name(), summary.kind.as_str(), summary.title),
+        }
+
+        self.outcomes.push(PublishOutcome {
+            publisher: sink.name().to_string(),
+            result,
+        });
+    }
+
+    /// 至少有一个渠道发布成功，否则返回错误
+    pub fn ensure_any_success(self) -> Result<Self> {
+        if self.success_count() == 0 {
+            let reasons = self.outcomes
+                .iter()
+                .filter_map(|outcome| match &outcome.result {
+                    Err(err) => Some(format!("{}: {err:?}", outcome.publisher)),
+                    Ok(_) => None,
+                })
+                .collect::<Vec<_>>();
+            anyhow::bail!("所有渠道发布失败: {}", reasons.join("; "));
+        }
+
+        Ok(self)
+    }
+}
+
+/// 已配置的全部发布渠道，同一份总结会依次发送到每个渠道
+///
+/// 先发布到外部平台，外部平台全部失败时不写入本地存档，这次发布视为失败，下次运行会重试
+#[derive(Default)]
+pub struct Publishers {
+    sinks: Vec<Box<dyn Publisher>>,
+}
+
+impl Publishers {
+    pub fn push(&mut self, sink: Box<dyn Publisher>) {
+        self.sinks.push(sink);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    pub async fn publish(&self, summary: &Summary) -> PublishReport {
+        let mut report = PublishReport::default();
+
+        let (external, local): (Vec<_>, Vec<_>) = self.sinks.iter().partition(|sink| sink.is_external());
+        for sink in &external {
+            report.publish_to(sink.as_ref(), summary).await;
+        }
+
+        if !external.is_empty() && report.success_count() == 0 {
+            info!("外部渠道全部发布失败，跳过本地存档: {}", summary.title);
+            return report;
+        }
+
+        for sink in &local {
+            report.publish_to(sink.as_ref(), summary).await;
+        }
+
+        report
+    }
+}
+
+/// 按最大字符数拆分文本，尽量在换行处拆分，单行超长时按字符拆分
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_len = line.chars().count();
+
+        if current_len + line_len > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        if line_len > max_chars {
+            for ch in line.chars() {
+                if current_len == max_chars {
+                    chunks.push(std::mem::take(&mut current));
+                    current_len = 0;
+                }
+                current.push(ch);
+                current_len += 1;
+            }
+        } else {
+            current.push_str(line);
+            current_len += line_len;
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks.retain(|chunk| !chunk.trim().is_empty());
+    chunks
+}
+
+/// 可以指定目标频道的发布渠道，`channel` 为 QQ 子频道ID、Telegram chat_id 或 Discord Webhook 地址
+pub const CHANNEL_PUBLISHERS: [&str; 3] = ["qq", "discord", "telegram"];
+
+/// 创建发布到指定频道的渠道，用于订阅
+pub async fn build_channel_publisher(app_state: &AppState, name: &str, channel: &str) -> Result<Box<dyn Publisher>> {
+    let publisher: Box<dyn Publisher> = match name {
+        "qq" => Box::new(QQThreadPublisher::new(app_state, false).await?.with_channel(channel)),
+        "discord" => Box::new(DiscordWebhookPublisher::with_webhook_url(channel)?),
+        "telegram" => Box::new(TelegramPublisher::for_chat(channel)?),
+        _ => anyhow::bail!("发布渠道不支持指定频道: {}", name),
+    };
+
+    Ok(publisher)
+}
+
+/// 根据环境变量 `PUBLISHERS`（逗号分隔，默认 `qq,feed`）创建发布渠道
+pub async fn build_publishers(app_state: &AppState) -> Result<Publishers> {
+    build_repo_publishers(app_state, &GithubRepo::parse(DEFAULT_REPO)?).await
+}
+
+/// 创建仓库的发布渠道，优先使用仓库配置的频道。
+/// QQ 没有配置的类型发布到环境变量中的子频道；Discord 和 Telegram 的环境变量只属于默认仓库，
+/// 其他仓库没有配置频道时跳过这两个渠道
+pub async fn build_repo_publishers(app_state: &AppState, repo: &GithubRepo) -> Result<Publishers> {
+    let names = env::var("PUBLISHERS").unwrap_or("qq,feed".to_string());
+
+    let mut publishers = Publishers::default();
+    for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let channels = repo.channels_of(name);
+        match name {
+            "qq" => publishers.push(Box::new(QQThreadPublisher::new(app_state, false).await?.with_channels(&channels))),
+            "discord" if repo.is_default() => {
+                publishers.push(Box::new(DiscordWebhookPublisher::new()?.with_channels(&channels)))
+            }
+            "telegram" if repo.is_default() => {
+                publishers.push(Box::new(TelegramPublisher::new()?.with_channels(&channels)))
+            }
+            "discord" | "telegram" if channels.is_empty() => {
+                info!("{} 没有配置 {} 频道，跳过该发布渠道", repo.full_name(), name);
+            }
+            "discord" => publishers.push(Box::new(DiscordWebhookPublisher::for_channels(&channels)?)),
+            "telegram" => publishers.push(Box::new(TelegramPublisher::for_channels(&channels)?)),
+            "feed" => publishers.push(Box::new(FeedPublisher::new(app_state.mysql.clone()))),
+            _ => anyhow::bail!("未知的发布渠道: {}", name),
+        }
+    }
+
+    if publishers.is_empty() {
+        anyhow::bail!("未配置任何发布渠道");
+    }
+
+    Ok(publishers)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::bots::publisher::{Publisher, Publishers, Summary, SummaryKind, split_text};
+
+    struct OkPublisher;
+    struct FailPublisher;
+    struct LocalPublisher;
+
+    #[async_trait::async_trait]
+    impl Publisher for OkPublisher {
+        fn name(&self) -> &str {
+            "ok"
+        }
+
+        async fn publish(&self, _summary: &Summary) -> Result<Option<String>> {
+            Ok(Some("1".to_string()))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Publisher for FailPublisher {
+        fn name(&self) -> &str {
+            "fail"
+        }
+
+        async fn publish(&self, _summary: &Summary) -> Result<Option<String>> {
+            anyhow::bail!("发布失败")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Publisher for LocalPublisher {
+        fn name(&self) -> &str {
+            "local"
+        }
+
+        async fn publish(&self, _summary: &Summary) -> Result<Option<String>> {
+            Ok(Some("1".to_string()))
+        }
+
+        fn is_external(&self) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_report() {
+        let summary = Summary::new(SummaryKind::Issues, "每日 Issues 总结：2025-11-10", "内容");
+
+        let mut publishers = Publishers::default();
+        publishers.push(Box::new(FailPublisher));
+        publishers.push(Box::new(OkPublisher));
+        let report = publishers.publish(&summary).await;
+        assert_eq!(report.outcomes.len(), 2);
+        assert_eq!(report.success_count(), 1);
+        assert!(report.ensure_any_success().is_ok());
+
+        let mut publishers = Publishers::default();
+        publishers.push(Box::new(FailPublisher));
+        let report = publishers.publish(&summary).await;
+        assert!(report.ensure_any_success().is_err());
+
+        // 外部渠道全部失败时不写入本地存档，不算发布成功
+        let mut publishers = Publishers::default();
+        publishers.push(Box::new(LocalPublisher));
+        publishers.push(Box::new(FailPublisher));
+        let report = publishers.publish(&summary).await;
+        assert_eq!(report.outcomes.len(), 1);
+        assert!(report.ensure_any_success().is_err());
+
+        // 外部渠道成功后再写入本地存档
+        let mut publishers = Publishers::default();
+        publishers.push(Box::new(LocalPublisher));
+        publishers.push(Box::new(OkPublisher));
+        let report = publishers.publish(&summary).await;
+        let names = report.outcomes.iter().map(|outcome| outcome.publisher.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["ok", "local"]);
+
+        // 只配置了本地存档时照常发布
+        let mut publishers = Publishers::default();
+        publishers.push(Box::new(LocalPublisher));
+        assert!(publishers.publish(&summary).await.ensure_any_success().is_ok());
+    }
+
+    #[test]
+    fn test_split_text() {
+        assert_eq!(split_text("abc\ndef\n", 4), vec!["abc\n", "def\n"]);
+        assert_eq!(split_text("abc\ndef", 10), vec!["abc\ndef"]);
+        assert_eq!(split_text("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(split_text("中文\n测试文本", 3), vec!["中文\n", "测试文", "本"]);
+        assert!(split_text("\n\n", 1).is_empty());
+    }
+}
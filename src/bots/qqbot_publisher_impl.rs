@@ -0,0 +1,145 @@
+use std::{collections::HashMap, env};
+
+use anyhow::Result;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::{
+    AppState,
+    bots::{
+        publisher::{Publisher, Summary, SummaryKind},
+        qqbot_client::QQBotClient,
+    },
+};
+
+/// QQ 频道帖子发布渠道
+pub struct QQThreadPublisher {
+    client: QQBotClient,
+    /// 子频道名称 -> 子频道ID
+    sub_channels: Mutex<Option<HashMap<String, String>>>,
+    /// 固定发布到该子频道，用于订阅
+    channel_id: Option<String>,
+    /// 仓库配置的子频道，优先于环境变量
+    channels: HashMap<SummaryKind, String>,
+}
+
+impl QQThreadPublisher {
+    pub async fn new(app_state: &AppState, sandbox: bool) -> Result<Self> {
+        Ok(Self {
+            client: QQBotClient::new(app_state, sandbox).await?,
+            sub_channels: Mutex::new(None),
+            channel_id: None,
+            channels: HashMap::new(),
+        })
+    }
+
+    pub fn with_channel(mut self, channel_id: &str) -> Self {
+        self.channel_id = Some(channel_id.to_string());
+        self
+    }
+
+    pub fn with_channels(mut self, channels: &HashMap<SummaryKind, String>) -> Self {
+        self.channels = channels.clone();
+        self
+    }
+
+    /// 根据总结类型选择子频道
+    async fn get_channel_id(&self, summary: &Summary) -> Result<String> {
+        if let Some(channel_id) = self.channel_id.as_ref().or(self.channels.get(&summary.kind)) {
+            return Ok(channel_id.clone());
+        }
+
+        let env_name = match summary.kind {
+            SummaryKind::Issues => "ISSUE_CHANNEL_ID",
+            SummaryKind::Prs => "PR_CHANNEL_ID",
+            SummaryKind::Commits => "COMMINT_CHANNEL_ID",
+            SummaryKind::MergeTrain => "MERGE_TRAIN_CHANNEL_ID",
+            SummaryKind::Release => "BEVY_NEWS_CHANNEL_ID",
+            SummaryKind::Milestone => {
+                let Some(topic) = &summary.topic else {
+                    anyhow::bail!("里程碑总结缺少里程碑名称");
+                };
+                return self.get_or_create_sub_channel(&get_channel_name(topic)).await;
+            }
+        };
+
+        Ok(env::var(env_name)?)
+    }
+
+    /// 查找子频道，不存在时创建
+    async fn get_or_create_sub_channel(&self, sub_channel_name: &str) -> Result<String> {
+        let mut sub_channels = self.sub_channels.lock().await;
+
+        if sub_channels.is_none() {
+            let list = self.client.get_sub_channels().await?;
+            *sub_channels = Some(
+                list.into_iter()
+                    .map(|sub_channel| (sub_channel.name, sub_channel.id))
+                    .collect()
+            );
+        }
+
+        let sub_channels = sub_channels.get_or_insert_default();
+        if let Some(id) = sub_channels.get(sub_channel_name) {
+            return Ok(id.clone());
+        }
+
+        let sub_channel = self.client.create_pub_sub_channel(sub_channel_name).await?;
+        sub_channels.insert(sub_channel.name, sub_channel.id.clone());
+
+        Ok(sub_channel.id)
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for QQThreadPublisher {
+    fn name(&self) -> &str {
+        "qq"
+    }
+
+    async fn publish(&self, summary: &Summary) -> Result<Option<String>> {
+        let channel_id = self.get_channel_id(summary).await?;
+
+        let res = self.client.send_any_thread(json!({
+            "title": summary.title,
+            "content": summary.text,
+            "format": 3
+        }), &channel_id)
+        .await?;
+
+        let task_id = res.get("task_id")
+            .and_then(|task_id| task_id.as_str())
+            .map(|task_id| task_id.to_string());
+
+        Ok(task_id)
+    }
+}
+
+// 计算QQ频道的子频道名称
+// 子频道名称必须长度为5
+// 其中数字，小数点，英文占用0.5个长度
+// 中文占用1个长度
+fn get_channel_name(
+    milestone_title: &str
+) -> String {
+
+    let milestone_len = milestone_title.chars().count() as f32 * 0.5;
+    let used_len = milestone_len.ceil() as usize;
+
+    let chinese_len = (5_usize).saturating_sub(used_len);
+
+    let text: String = "里程碑".chars().take(chinese_len).collect();
+
+    format!("{}{}", milestone_title, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bots::qqbot_publisher_impl::get_channel_name;
+
+    #[test]
+    fn test_get_channel_name() {
+        assert_eq!(get_channel_name("0.18"), "0.18里程碑");
+        assert_eq!(get_channel_name("0.17.3"), "0.17.3里程");
+    }
+}
@@ -0,0 +1,489 @@
+use std::{collections::HashMap, env, time::Duration};
+
+use anyhow::Result;
+use reqwest::{Client, ClientBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use crate::bots::{
+    REQUEST_TIME_OUT_SEC,
+    publisher::{Publisher, Summary, SummaryKind},
+};
+
+// Telegram 单条消息的长度限制
+const MESSAGE_MAX_CHARS: usize = 4096;
+// 转义后长度最多翻倍，超长的行先按该长度拆分再转换
+const RAW_LINE_MAX_CHARS: usize = 2000;
+
+// MarkdownV2 中需要转义的字符
+const SPECIAL_CHARS: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+#[derive(Debug, Deserialize)]
+struct TelegramRes<T> {
+    ok: bool,
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramMessage {
+    message_id: i64,
+}
+
+/// Telegram Bot 发布渠道
+pub struct TelegramPublisher {
+    client: Client,
+    base_url: String,
+    token: String,
+    /// 总结类型 -> 目标 chat_id
+    chat_ids: HashMap<SummaryKind, String>,
+}
+
+impl TelegramPublisher {
+    pub fn new() -> Result<Self> {
+        let token = env::var("TELEGRAM_BOT_TOKEN")?;
+        let base_url = env::var("TELEGRAM_API_URL").unwrap_or("https://api.telegram.org/".to_string());
+
+        let mut chat_ids = HashMap::new();
+        for (kind, env_name) in [
+            (SummaryKind::Issues, "TELEGRAM_ISSUE_CHAT_ID"),
+            (SummaryKind::Prs, "TELEGRAM_PR_CHAT_ID"),
+            (SummaryKind::Commits, "TELEGRAM_COMMIT_CHAT_ID"),
+            (SummaryKind::MergeTrain, "TELEGRAM_MERGE_TRAIN_CHAT_ID"),
+            (SummaryKind::Milestone, "TELEGRAM_MILESTONE_CHAT_ID"),
+            (SummaryKind::Release, "TELEGRAM_RELEASE_CHAT_ID"),
+        ] {
+            if let Ok(chat_id) = env::var(env_name) && !chat_id.is_empty() {
+                chat_ids.insert(kind, chat_id);
+            }
+        }
+
+        Self::with_base_url(&base_url, &token, chat_ids)
+    }
+
+    /// 所有类型都发送到同一个 chat，用于订阅
+    pub fn for_chat(chat_id: &str) -> Result<Self> {
+        let chat_ids = SummaryKind::ALL
+            .into_iter()
+            .map(|kind| (kind, chat_id.to_string()))
+            .collect();
+
+        Self::for_channels(&chat_ids)
+    }
+
+    /// 只发送到仓库配置的 chat，不使用环境变量中的 chat_id
+    pub fn for_channels(chat_ids: &HashMap<SummaryKind, String>) -> Result<Self> {
+        let token = env::var("TELEGRAM_BOT_TOKEN")?;
+        let base_url = env::var("TELEGRAM_API_URL").unwrap_or("https://api.telegram.org/".to_string());
+
+        Self::with_base_url(&base_url, &token, chat_ids.clone())
+    }
+
+    /// 仓库配置的 chat 优先于环境变量
+    pub fn with_channels(mut self, chat_ids: &HashMap<SummaryKind, String>) -> Self {
+        self.chat_ids.extend(chat_ids.iter().map(|(kind, chat_id)| (*kind, chat_id.clone())));
+        self
+    }
+
+    pub fn with_base_url(base_url: &str, token: &str, chat_ids: HashMap<SummaryKind, String>) -> Result<Self> {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(REQUEST_TIME_OUT_SEC))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.to_string(),
+            token: token.to_string(),
+            chat_ids,
+        })
+    }
+
+    fn get_url(&self, method: &str) -> Result<String> {
+        Ok(Url::parse(&self.base_url)?.join(&format!("bot{}/{}", self.token, method))?.to_string())
+    }
+
+    /// 发送一条 MarkdownV2 消息，返回消息ID
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<i64> {
+        let res = self.client.post(self.get_url("sendMessage")?)
+            .json(&json!({
+                "chat_id": chat_id,
+                "text": text,
+                "parse_mode": "MarkdownV2",
+                "link_preview_options": { "is_disabled": true }
+            }))
+            .send()
+            .await?;
+
+        let data: TelegramRes<TelegramMessage> = res.json().await?;
+
+        match data.result {
+            Some(message) if data.ok => Ok(message.message_id),
+            _ => anyhow::bail!("Telegram 发送消息失败: {}", data.description.unwrap_or_default()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for TelegramPublisher {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn publish(&self, summary: &Summary) -> Result<Option<String>> {
+        let Some(chat_id) = self.chat_ids.get(&summary.kind) else {
+            anyhow::bail!("未配置 {} 的 Telegram chat_id", summary.kind.as_str());
+        };
+
+        let text = format!("# {}\n\n{}", summary.title, summary.text);
+
+        let mut first_id = None;
+        for chunk in to_markdown_v2_chunks(&text) {
+            let id = self.send_message(chat_id, &chunk).await?;
+            first_id.get_or_insert(id.to_string());
+        }
+
+        Ok(first_id)
+    }
+}
+
+/// 把 Markdown 转换为 Telegram MarkdownV2，并按消息长度拆分
+/// 代码块跨消息时会在拆分处闭合并在下一条消息重新打开
+pub fn to_markdown_v2_chunks(text: &str) -> Vec<String> {
+    let mut builder = ChunkBuilder::default();
+    let mut code_fence: Option<String> = None;
+
+    for raw_line in text.lines() {
+        let trimmed = raw_line.trim_start();
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            if code_fence.take().is_some() {
+                builder.push_line("```", None);
+            } else {
+                let fence = format!("```{}", escape_code(lang.trim()));
+                builder.push_line(&fence, None);
+                code_fence = Some(fence);
+            }
+            continue;
+        }
+
+        let chars = raw_line.chars().collect::<Vec<_>>();
+        if chars.is_empty() {
+            builder.push_line("", code_fence.as_deref());
+            continue;
+        }
+
+        for piece in chars.chunks(RAW_LINE_MAX_CHARS) {
+            let piece = piece.iter().collect::<String>();
+            let line = if code_fence.is_some() {
+                escape_code(&piece)
+            } else {
+                convert_line(&piece)
+            };
+            builder.push_line(&line, code_fence.as_deref());
+        }
+    }
+
+    if code_fence.is_some() {
+        builder.push_line("```", None);
+    }
+
+    builder.finish()
+}
+
+#[derive(Default)]
+struct ChunkBuilder {
+    chunks: Vec<String>,
+    current: String,
+    current_len: usize,
+}
+
+impl ChunkBuilder {
+    /// `code_fence` 为当前所在代码块的开头，拆分时用于闭合和重新打开代码块
+    fn push_line(&mut self, line: &str, code_fence: Option<&str>) {
+        let line_len = line.chars().count();
+        let close_len = if code_fence.is_some() { 4 } else { 0 };
+
+        if self.current_len > 0 && self.current_len + 1 + line_len + close_len > MESSAGE_MAX_CHARS {
+            if code_fence.is_some() {
+                self.current.push_str("\n```");
+            }
+            self.chunks.push(std::mem::take(&mut self.current));
+            self.current_len = 0;
+
+            if let Some(code_fence) = code_fence {
+                self.current.push_str(code_fence);
+                self.current_len = code_fence.chars().count();
+            }
+        }
+
+        if self.current_len > 0 {
+            self.current.push('\n');
+            self.current_len += 1;
+        }
+        self.current.push_str(line);
+        self.current_len += line_len;
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if !self.current.trim().is_empty() {
+            self.chunks.push(self.current);
+        }
+        self.chunks
+    }
+}
+
+/// 转换一行 Markdown，处理标题、列表、引用，其余按行内格式转换
+fn convert_line(line: &str) -> String {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+
+    // 标题转为粗体
+    let heading_level = content.chars().take_while(|ch| *ch == '#').count();
+    if (1..=6).contains(&heading_level) && content[heading_level..].starts_with(' ') {
+        let title = content[heading_level..].trim().replace("**", "");
+        return format!("{}*{}*", indent, convert_inline(&title));
+    }
+
+    // 分割线
+    let first = content.chars().next();
+    if content.len() >= 3
+        && matches!(first, Some('-' | '*' | '_'))
+        && content.chars().all(|ch| Some(ch) == first)
+    {
+        return "——————".to_string();
+    }
+
+    // 引用
+    if let Some(quote) = content.strip_prefix('>') {
+        return format!(">{}", convert_inline(quote.trim_start()));
+    }
+
+    // 无序列表
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = content.strip_prefix(marker) {
+            return format!("{}• {}", indent, convert_inline(item));
+        }
+    }
+
+    // 有序列表
+    let digits = content.chars().take_while(|ch| ch.is_ascii_digit()).count();
+    if digits > 0 && content[digits..].starts_with(". ") {
+        return format!("{}{}\\. {}", indent, &content[..digits], convert_inline(&content[digits + 2..]));
+    }
+
+    format!("{}{}", indent, convert_inline(content))
+}
+
+/// 转换行内格式：`code`、**粗体**、*斜体*、~~删除线~~、[链接](url)
+fn convert_inline(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        match ch {
+            '`' => {
+                if let Some(end) = find(&chars, i + 1, "`") {
+                    let code = chars[i + 1..end].iter().collect::<String>();
+                    out.push('`');
+                    out.push_str(&escape_code(&code));
+                    out.push('`');
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if let Some(end) = find(&chars, i + 2, "**") && end > i + 2 {
+                    let inner = chars[i + 2..end].iter().collect::<String>();
+                    out.push('*');
+                    out.push_str(&convert_inline(&inner));
+                    out.push('*');
+                    i = end + 2;
+                    continue;
+                }
+            }
+            '*' => {
+                if let Some(end) = find(&chars, i + 1, "*")
+                    && end > i + 1
+                    && !chars[i + 1].is_whitespace()
+                {
+                    let inner = chars[i + 1..end].iter().collect::<String>();
+                    out.push('_');
+                    out.push_str(&convert_inline(&inner));
+                    out.push('_');
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '~' if chars.get(i + 1) == Some(&'~') => {
+                if let Some(end) = find(&chars, i + 2, "~~") && end > i + 2 {
+                    let inner = chars[i + 2..end].iter().collect::<String>();
+                    out.push('~');
+                    out.push_str(&convert_inline(&inner));
+                    out.push('~');
+                    i = end + 2;
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some(text_end) = find(&chars, i + 1, "](")
+                    && let Some(url_end) = find(&chars, text_end + 2, ")")
+                {
+                    let link_text = chars[i + 1..text_end].iter().collect::<String>();
+                    let url = chars[text_end + 2..url_end].iter().collect::<String>();
+                    out.push('[');
+                    out.push_str(&convert_inline(&link_text));
+                    out.push_str("](");
+                    out.push_str(&escape_link_url(&url));
+                    out.push(')');
+                    i = url_end + 1;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        if SPECIAL_CHARS.contains(&ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+        i += 1;
+    }
+
+    out
+}
+
+/// 从 `start` 开始查找 `pattern`，返回其起始位置
+fn find(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    if start > chars.len() {
+        return None;
+    }
+
+    chars[start..]
+        .windows(pattern.len())
+        .position(|window| window == pattern.as_slice())
+        .map(|pos| pos + start)
+}
+
+/// 代码中只需要转义 ` 和 \
+fn escape_code(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`")
+}
+
+/// 链接地址中只需要转义 ) 和 \
+fn escape_link_url(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use actix_web::{App, HttpResponse, HttpServer, post, web};
+    use serde_json::json;
+
+    use crate::bots::{
+        publisher::{Publisher, Summary, SummaryKind},
+        telegram_client::{TelegramPublisher, convert_inline, to_markdown_v2_chunks},
+    };
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(convert_inline("v0.18 (beta)!"), "v0\\.18 \\(beta\\)\\!");
+        assert_eq!(convert_inline("a_b*c"), "a\\_b\\*c");
+    }
+
+    #[test]
+    fn test_to_markdown_v2() {
+        let markdown = "## 每日 Bevy Issue 总结\n\
+            - **标题**: 修复 `Query<&mut T>` 的问题 (#123)\n\
+            1. 链接: [GitHub Issue #123](https://github.com/bevyengine/bevy/issues/123)\n\
+            > 术语解释: *ECS* 是 Entity-Component-System\n\
+            ~~废弃~~ snake_case\n\
+            ---\n\
+            ```rust\n\
+            fn main() { println!(\"`\"); }\n\
+            ```";
+
+        let chunks = to_markdown_v2_chunks(markdown);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0],
+            "*每日 Bevy Issue 总结*\n\
+            • *标题*: 修复 `Query<&mut T>` 的问题 \\(\\#123\\)\n\
+            1\\. 链接: [GitHub Issue \\#123](https://github.com/bevyengine/bevy/issues/123)\n\
+            >术语解释: _ECS_ 是 Entity\\-Component\\-System\n\
+            ~废弃~ snake\\_case\n\
+            ——————\n\
+            ```rust\n\
+            fn main() { println!(\"\\`\"); }\n\
+            ```"
+        );
+    }
+
+    #[test]
+    fn test_unclosed_markers_are_escaped() {
+        let chunks = to_markdown_v2_chunks("2 * 3 = 6, [link, `code");
+        assert_eq!(chunks, vec!["2 \\* 3 \\= 6, \\[link, \\`code"]);
+    }
+
+    #[test]
+    fn test_split_keeps_code_block() {
+        let markdown = format!("```\n{}```", "let a = 1;\n".repeat(1000));
+        let chunks = to_markdown_v2_chunks(&markdown);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 4096);
+            assert!(chunk.starts_with("```"));
+            assert!(chunk.ends_with("```"));
+        }
+    }
+
+    type Received = web::Data<Mutex<Vec<serde_json::Value>>>;
+
+    #[post("/bottest-token/sendMessage")]
+    async fn send_message(received: Received, body: web::Json<serde_json::Value>) -> HttpResponse {
+        let mut received = received.lock().unwrap();
+        received.push(body.into_inner());
+        HttpResponse::Ok().json(json!({ "ok": true, "result": { "message_id": received.len() } }))
+    }
+
+    #[actix_web::test]
+    async fn test_publish_to_bot_api() {
+        let received: Received = web::Data::new(Mutex::new(Vec::new()));
+
+        let app_data = received.clone();
+        let server = HttpServer::new(move || App::new().app_data(app_data.clone()).service(send_message))
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let chat_ids = HashMap::from([(SummaryKind::Issues, "-100123".to_string())]);
+        let publisher = TelegramPublisher::with_base_url(&format!("http://{}/", addr), "test-token", chat_ids).unwrap();
+
+        let text = "第一行.\n".repeat(1000);
+        let summary = Summary::new(SummaryKind::Issues, "每日 Issues 总结：2025-11-10", &text);
+        let id = publisher.publish(&summary).await.unwrap();
+        assert_eq!(id, Some("1".to_string()));
+
+        // 未配置 chat_id 的类型
+        let summary = Summary::new(SummaryKind::Prs, "每日 PRs 总结：2025-11-10", &text);
+        assert!(publisher.publish(&summary).await.is_err());
+
+        let received = received.lock().unwrap();
+        assert!(received.len() > 1);
+        for message in received.iter() {
+            assert_eq!(message["chat_id"], "-100123");
+            assert_eq!(message["parse_mode"], "MarkdownV2");
+            assert!(message["text"].as_str().unwrap().chars().count() <= 4096);
+        }
+    }
+}
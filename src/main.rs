@@ -2,7 +2,7 @@ use std::{env, time::Duration};
 
 use actix_cors::Cors;
 use actix_web::{web, App, HttpResponse, HttpServer};
-use api::{admin::admin, client::client};
+use api::{admin::{admin, init_admin_user}, client::client};
 use dotenvy::dotenv;
 use migration::{
     sea_orm::{ConnectOptions, Database, DatabaseConnection},
@@ -25,11 +25,25 @@ pub struct AppState {
     redis: Client,
 }
 
+impl AppState {
+    /// 根据环境变量创建，用于测试
+    #[cfg(test)]
+    pub async fn new_with_default() -> Self {
+        let mysql_url = env::var("DATABASE_URL").expect("请配置数据库链接");
+        let redis_url = env::var("REDIS").expect("请配置Redis链接");
+
+        Self {
+            mysql: Database::connect(mysql_url).await.expect("连接MYSQL数据库失败"),
+            redis: redis::Client::open(redis_url).expect("连接Redis失败"),
+        }
+    }
+}
+
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
 
-use crate::tasks::{bsky_task::watch_merge_train_feed::spawn_merge_train_task, github_task::{watch_commits::get_new_commits, watch_issue_list::get_new_issues, watch_milestones::spawn_milestone_task, watch_pr::get_new_prs}};
+use crate::tasks::{github_task::repo::init_watched_repos, job_registry::{init_job_schedules, spawn_scheduler}, prompt_template::init_prompt_templates};
 
 #[cfg(not(target_env = "msvc"))]
 #[global_allocator]
@@ -66,14 +80,27 @@ async fn main() -> std::io::Result<()> {
         redis: redis_client.clone(),
     };
 
+    init_admin_user(&app_state)
+        .await
+        .expect("初始化管理员失败");
+
     let web_app_state = web::Data::new(app_state.clone());
 
-    // 异步任务
-    get_new_issues().unwrap();
-    get_new_commits().unwrap();
-    spawn_milestone_task(app_state.clone()).unwrap();
-    spawn_merge_train_task(app_state.clone());
-    get_new_prs().unwrap();
+    // 监听的仓库保存在 watched_repo 表中
+    init_watched_repos(&app_state)
+        .await
+        .expect("初始化监听仓库失败");
+
+    // 提示词模板保存在 prompt_template 表中，可以通过管理接口修改
+    init_prompt_templates(&app_state)
+        .await
+        .expect("初始化提示词模板失败");
+
+    // 异步任务，定时配置保存在 job_schedule 表中
+    init_job_schedules(&app_state)
+        .await
+        .expect("初始化定时任务失败");
+    spawn_scheduler(app_state.clone());
 
     HttpServer::new(move || {
         let cors = Cors::permissive();
@@ -1,53 +1,37 @@
-use std::{env, sync::Arc, time::Instant};
+use std::time::Instant;
 
-use actix_rt::spawn;
 use anyhow::Result;
-use deepseek_api::{
-    CompletionsRequestBuilder, DeepSeekClient, RequestBuilder,
-    request::{MessageRequest, SystemMessageRequest},
-};
-use log::{error, info};
+use log::info;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
     ColumnTrait, EntityTrait, QueryFilter,
 };
-use tokio_schedule::{Job, every};
 
 use crate::{
     AppState,
     bots::{
-        bsky_client::BskyClient, deepseek_client::build_deepseek_client, qqbot_client::QQBotClient,
+        bsky_client::BskyClient,
+        llm_client::{LlmProvider, build_llm_provider},
+        publisher::{Publishers, Summary, SummaryKind, build_publishers},
     },
     tasks::{
         bsky_task::{
             BEVY_MERGE_TRAIN_API,
             feed_data::{Feature, Feed},
         },
-        github_task::get_first_deepseek_response,
+        github_task::repo::DEFAULT_REPO,
+        glossary::load_glossary,
+        map_reduce::{MapReducePrompt, map_reduce},
+        prompt_template::{PromptVars, load_template},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, TaskStats},
     },
 };
 
-pub fn spawn_merge_train_task(app_state: AppState) {
-    info!("开始定时抓取 merge train 任务");
-
-    let app_state = Arc::new(app_state);
-
-    let every_day_task = every(1).day().at(12, 20, 00).perform(move || {
-        let state = app_state.as_ref().clone();
-        async {
-            if let Err(err) = run_merge_train_task(state).await {
-                error!("{err:?}");
-            }
-        }
-    });
-
-    spawn(every_day_task);
-}
-
-pub async fn run_merge_train_task(app_state: AppState) -> Result<()> {
-    let deepseek_client = build_deepseek_client()?;
-    let qq_client = QQBotClient::new_with_default(false).await?;
+pub async fn run_merge_train_task(app_state: AppState) -> Result<TaskStats> {
+    let llm = build_llm_provider(TaskName::MergeTrain.as_str())?;
+    let publishers = build_publishers(&app_state).await?;
     let bsk_client = BskyClient::new();
 
     let merge_train_list = get_first_page_merge_train(&bsk_client).await?;
@@ -56,21 +40,24 @@ pub async fn run_merge_train_task(app_state: AppState) -> Result<()> {
         &app_state,
         &bsk_client,
         merge_train_list,
-        &deepseek_client,
-        &qq_client,
+        llm.as_ref(),
+        &publishers,
     )
-    .await?;
-
-    Ok(())
+    .await
 }
 
 pub async fn process_post_thread(
     app_state: &AppState,
     client: &BskyClient,
     post_list: Vec<MergeTrainPost>,
-    deepseek_client: &DeepSeekClient,
-    qq_client: &QQBotClient,
-) -> Result<()> {
+    llm: &dyn LlmProvider,
+    publishers: &Publishers,
+) -> Result<TaskStats> {
+    let mut stats = TaskStats::default();
+    let map_template = load_template(app_state, "merge-train").await?;
+    let reduce_template = load_template(app_state, "merge-train-reduce").await?;
+    let glossary = load_glossary(app_state).await;
+
     for post in post_list {
         let title = format!("MergeTrain: {}", post.date);
 
@@ -86,62 +73,35 @@ pub async fn process_post_thread(
         let thread_post: serde_json::Value = client.get_pub_post_thread(&post.uri).await?;
         // let thread_post_text = &thread_post.thread.post.record.text;
 
-        let text = serde_json::to_string(&thread_post)?;
-
-        let chat_messages = vec![
-            MessageRequest::System(SystemMessageRequest::new(
-                r"你是一个专业的数据分析师和内容摘要专家。你的任务是解析给定的JSON数据，该数据代表一个社交媒体帖子及其回复。你需要从中提取关键信息，并以清晰的Markdown格式生成一份摘要，向读者解释这个帖子的主要内容和讨论。
-
-                请遵循以下步骤：
+        let items = thread_items(&thread_post)?;
 
-                1.  **识别主贴内容**：
-                    *   找到帖子的主要作者：Alice I Cecile。
-                    *   检查主贴是否包含文本。如果主贴包含的是一个嵌入式外链（类型为 `app.bsky.embed.external`），请提取该外链的以下信息：
-                        *   链接标题 (`title`)
-                        *   链接描述 (`description`)
-                        *   链接地址 (`uri`)
-
-                2.  **总结回复串**：
-                    *   JSON数据中包含一个嵌套的回复链（`replies`）。这些回复详细阐述了Alice I Cecile的观点。
-                    *   请按顺序阅读这些回复（`text` 字段），并将其内容整合成一个连贯的段落或几个要点。这部分是帖子的核心思想。
-                    *   注意，这个帖子是Alice I Cecile在解释自己审查一个技术性PR（Pull Request）时的思考过程。
-
-                3.  **识别其他回复**：
-                    *   检查是否有来自其他用户的独立回复，并简要提及。
-
-                4.  **格式化输出**：
-                    *   使用Markdown格式。
-                    *   为摘要起一个合适的标题。
-                    *   使用标题、列表和引用块来组织内容，使其易于阅读。
-                    *   将提取的外链格式化为Markdown链接。
-
-                请根据下面的JSON数据生成摘要：",
-            )),
-            MessageRequest::user(&text),
-        ];
+        let vars = PromptVars::new(&post.date, DEFAULT_REPO, items.len());
+        let map_prompt = map_template.render(&vars);
+        let reduce_prompt = reduce_template.render(&vars);
+        // 回复分批时，每批都带上主贴，否则后面的批次不知道回复的是什么
+        let Some((main_post, replies)) = items.split_first() else {
+            anyhow::bail!("帖子串为空: {}", post.uri);
+        };
+        let prompt = MapReducePrompt { map: &map_prompt, reduce: &reduce_prompt, context: Some(main_post) };
 
         let now = Instant::now();
-        info!("开始请求AI总结");
-
-        let ds_res = CompletionsRequestBuilder::new(&chat_messages)
-            .use_model(deepseek_api::response::ModelType::DeepSeekReasoner)
-            .stream(false)
-            .max_tokens(8192)
-            .unwrap()
-            .do_request(deepseek_client)
-            .await?;
+        info!("开始请求AI总结, 后端: {}", llm.name());
 
-        let ds_res_text = get_first_deepseek_response(ds_res)?;
-        info!("AI总结完成, 耗时: {}秒", now.elapsed().as_secs_f32());
+        let output = map_reduce(llm, &title, &prompt, &glossary, replies, Some(8192)).await?;
+
+        let llm_latency = now.elapsed();
+        info!("AI总结完成, 耗时: {}秒, Token: {}", llm_latency.as_secs_f32(), output.usage.total_tokens);
 
         // 帖子发布
-        qq_client
-            .send_thread(
-                &title,
-                &ds_res_text,
-                &env::var("MERGE_TRAIN_CHANNEL_ID").unwrap(),
-            )
-            .await?;
+        let summary = Summary::new(SummaryKind::MergeTrain, &title, &output.text)
+            .with_links(vec![post.web_url()]);
+        let provenance = Provenance {
+            source_ids: vec![post.cid.clone()],
+            prompt_version: format!("{},{}", map_template.version_tag(), reduce_template.version_tag()),
+            model: output.model.clone(),
+            raw_output: output.text,
+        };
+        publish_and_record(app_state, publishers, &summary, &provenance).await?;
 
         // 数据库保存
         let new_milestone = entity::merge_train::ActiveModel {
@@ -151,9 +111,34 @@ pub async fn process_post_thread(
         };
 
         new_milestone.insert(&app_state.mysql).await?;
+
+        stats += TaskStats::new(1, llm_latency).with_model(&output.model);
     }
 
-    Ok(())
+    Ok(stats)
+}
+
+/// 把帖子串拆成主贴和每条一级回复，回复较多时可以分批总结
+///
+/// 第一条是主贴，不包含回复，每条一级回复包含它下面的回复
+pub fn thread_items(thread_post: &serde_json::Value) -> Result<Vec<String>> {
+    let Some(thread) = thread_post.get("thread") else {
+        return Ok(vec![serde_json::to_string(thread_post)?]);
+    };
+
+    let mut main_post = thread.clone();
+    let replies = main_post
+        .as_object_mut()
+        .and_then(|main_post| main_post.remove("replies"));
+
+    let mut items = vec![serde_json::to_string(&main_post)?];
+    if let Some(serde_json::Value::Array(replies)) = replies {
+        for reply in replies {
+            items.push(serde_json::to_string(&reply)?);
+        }
+    }
+
+    Ok(items)
 }
 
 pub struct MergeTrainPost {
@@ -162,6 +147,17 @@ pub struct MergeTrainPost {
     pub date: String,
 }
 
+impl MergeTrainPost {
+    /// 帖子的网页地址，`at://{did}/app.bsky.feed.post/{rkey}` -> `https://bsky.app/profile/{did}/post/{rkey}`
+    pub fn web_url(&self) -> String {
+        let path = self.uri.trim_start_matches("at://");
+        let did = path.split('/').next().unwrap_or_default();
+        let rkey = path.rsplit('/').next().unwrap_or_default();
+
+        format!("https://bsky.app/profile/{}/post/{}", did, rkey)
+    }
+}
+
 pub async fn get_first_page_merge_train(client: &BskyClient) -> Result<Vec<MergeTrainPost>> {
     let feed_data: Feed = client.get_pub(BEVY_MERGE_TRAIN_API).await?;
 
@@ -197,9 +193,50 @@ pub async fn get_first_page_merge_train(client: &BskyClient) -> Result<Vec<Merge
 mod tests {
     use crate::{
         bots::bsky_client::BskyClient,
-        tasks::bsky_task::{BEVY_MERGE_TRAIN_API, feed_data::Feed, post_data::ThreadPost},
+        tasks::bsky_task::{
+            BEVY_MERGE_TRAIN_API,
+            feed_data::Feed,
+            post_data::ThreadPost,
+            watch_merge_train_feed::{MergeTrainPost, thread_items},
+        },
     };
 
+    #[test]
+    fn test_post_web_url() {
+        let post = MergeTrainPost {
+            uri: "at://did:plc:fjg6pzaigjmfpsfnbyp6m5oc/app.bsky.feed.post/3m4qp6zgvzc2j".to_string(),
+            cid: "".to_string(),
+            date: "2025-11-10".to_string(),
+        };
+
+        assert_eq!(
+            post.web_url(),
+            "https://bsky.app/profile/did:plc:fjg6pzaigjmfpsfnbyp6m5oc/post/3m4qp6zgvzc2j"
+        );
+    }
+
+    #[test]
+    fn test_thread_items() {
+        let thread = serde_json::json!({
+            "thread": {
+                "post": { "record": { "text": "主贴" } },
+                "replies": [
+                    { "post": { "record": { "text": "回复1" } }, "replies": [{ "post": { "record": { "text": "回复1-1" } } }] },
+                    { "post": { "record": { "text": "回复2" } } }
+                ]
+            }
+        });
+
+        let items = thread_items(&thread).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], r#"{"post":{"record":{"text":"主贴"}}}"#);
+        assert!(items[1].contains("回复1-1"));
+        assert!(items[2].contains("回复2"));
+
+        // 没有 thread 字段时整体作为一条
+        assert_eq!(thread_items(&serde_json::json!({ "a": 1 })).unwrap(), vec![r#"{"a":1}"#.to_string()]);
+    }
+
     #[tokio::test]
     async fn test_mergetrain() {
         dotenvy::dotenv().ok();
@@ -0,0 +1,170 @@
+use std::{collections::BTreeMap, time::{Duration, Instant}};
+
+use anyhow::Result;
+use futures_util::{StreamExt, TryStreamExt, stream};
+use log::info;
+
+use crate::{bots::llm_client::{ChatMessage, LlmProvider}, tasks::glossary::Glossary};
+
+// 没有领域标签的条目
+pub const OTHER_AREA: &str = "其他";
+// 同时请求 AI 的分组数量
+const CONCURRENT_REQUESTS: usize = 4;
+
+/// 带有 GitHub 标签的条目
+pub trait Labeled {
+    fn labels(&self) -> &[String];
+
+    /// 第一个指定前缀的标签，例如 `A-` 领域、`C-` 类别、`S-` 状态、`D-` 难度
+    fn find_label(&self, prefix: &str) -> Option<&str> {
+        self.labels()
+            .iter()
+            .find(|label| label.starts_with(prefix))
+            .map(|label| label.as_str())
+    }
+
+    /// 第一个 `A-*` 领域标签，有多个时只归入第一个，避免重复
+    fn area(&self) -> &str {
+        self.find_label("A-").unwrap_or(OTHER_AREA)
+    }
+}
+
+/// 交给 AI 的一条数据和它的标签
+#[derive(Debug, Clone)]
+pub struct DigestItem {
+    pub labels: Vec<String>,
+    pub text: String,
+}
+
+impl Labeled for DigestItem {
+    fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+/// 按领域标签分组，分组按名称排序，没有领域标签的放在最后；组内按类别标签排序
+pub fn group_by_area<T: Labeled>(items: &[T]) -> Vec<(&str, Vec<&T>)> {
+    let mut groups: BTreeMap<&str, Vec<&T>> = BTreeMap::new();
+    for item in items {
+        groups.entry(item.area()).or_default().push(item);
+    }
+
+    let other = groups.remove(OTHER_AREA);
+    let mut groups = groups.into_iter().collect::<Vec<_>>();
+    if let Some(other) = other {
+        groups.push((OTHER_AREA, other));
+    }
+
+    // 稳定排序，同类别的保持原来的顺序
+    for (_, group) in groups.iter_mut() {
+        group.sort_by_key(|item| item.find_label("C-").unwrap_or("~"));
+    }
+
+    groups
+}
+
+/// 一组需要翻译的条目
+#[derive(Debug, Clone)]
+pub struct TranslateGroup {
+    /// 分组说明，例如 `新开的Issue / A-ECS`
+    pub context: String,
+    pub lines: Vec<String>,
+}
+
+/// 每组的翻译结果，顺序和输入一致
+#[derive(Debug, Clone)]
+pub struct GroupTranslation {
+    pub texts: Vec<String>,
+    /// 实际回答的模型，有多个时逗号分隔
+    pub model: String,
+    pub llm_latency: Duration,
+}
+
+/// 分组已经在代码中确定，AI 只负责每组内容的翻译和术语解释
+///
+/// 每组只附带组内出现的术语，翻译完成后检查是否使用了术语表译法
+pub async fn translate_groups(
+    llm: &dyn LlmProvider,
+    system_prompt: &str,
+    glossary: &Glossary,
+    groups: &[TranslateGroup],
+) -> Result<GroupTranslation> {
+    let now = Instant::now();
+    info!("开始请求AI翻译, 后端: {}, 共{}组", llm.name(), groups.len());
+
+    let outputs = stream::iter(groups)
+        .map(|group| async move {
+            let content = format!("分组: {}\n{}", group.context, group.lines.join("\n"));
+            let terms = glossary.relevant(&content);
+            let chat_messages = vec![
+                ChatMessage::system(&terms.inject(system_prompt)),
+                ChatMessage::user(&content),
+            ];
+
+            let output = llm.chat(&chat_messages, Some(8192)).await?;
+            terms.check(&format!("分组 {}", group.context), &output.text);
+
+            Ok::<_, anyhow::Error>(output)
+        })
+        .buffered(CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let llm_latency = now.elapsed();
+    let total_tokens = outputs.iter().map(|output| output.usage.total_tokens).sum::<u64>();
+    info!("AI翻译完成, 耗时: {}秒, Token: {}", llm_latency.as_secs_f32(), total_tokens);
+
+    // 降级时不同分组可能由不同模型回答
+    let mut models: Vec<&str> = vec![];
+    for output in &outputs {
+        if !models.contains(&output.model.as_str()) {
+            models.push(&output.model);
+        }
+    }
+    let model = models.join(",");
+
+    Ok(GroupTranslation {
+        texts: outputs.into_iter().map(|output| output.text).collect(),
+        model,
+        llm_latency,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::github_task::label_group::{DigestItem, Labeled, group_by_area};
+
+    fn item(text: &str, labels: &[&str]) -> DigestItem {
+        DigestItem {
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_area() {
+        let items = vec![
+            item("1", &["A-Rendering", "C-Feature"]),
+            item("2", &["C-Usability"]),
+            item("3", &["A-ECS", "A-Rendering"]),
+            item("4", &["A-Rendering", "C-Bug"]),
+            item("5", &["A-Rendering"]),
+            item("6", &["A-Rendering", "C-Bug", "D-Trivial"]),
+        ];
+
+        let groups = group_by_area(&items)
+            .into_iter()
+            .map(|(area, group)| (area, group.iter().map(|item| item.text.as_str()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(groups, vec![
+            ("A-ECS", vec!["3"]),
+            // 组内按类别排序，没有类别的在最后
+            ("A-Rendering", vec!["4", "6", "1", "5"]),
+            ("其他", vec!["2"]),
+        ]);
+
+        assert_eq!(items[5].find_label("D-"), Some("D-Trivial"));
+        assert_eq!(items[1].find_label("S-"), None);
+    }
+}
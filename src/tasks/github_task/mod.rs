@@ -1,35 +1,132 @@
+use std::env;
+
 use anyhow::Result;
-use deepseek_api::response::{ChatCompletion, ChatCompletionStream, ChatResponse, JSONChoiceStream};
 use log::*;
+use octocrab::{Octocrab, Page};
+use serde::de::DeserializeOwned;
 
+pub mod label_group;
+pub mod repo;
 pub mod watch_issue_list;
 pub mod watch_milestones;
+pub mod watch_migration_guide;
 pub mod watch_commits;
 pub mod watch_pr;
+pub mod watch_releases;
+pub mod webhook;
 
-// const BEVY_GITHUB: &str = "https://github.com/bevyengine/bevy";
-const BEVY_OWNER: &str = "bevyengine";
-const BEVY_REPO: &str = "bevy";
+/// GitHub 列表接口的分页限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimit {
+    /// 每页数量，GitHub 最大为100
+    pub per_page: u8,
+    /// 最多读取的页数，避免异常情况下无限请求
+    pub max_pages: usize,
+}
 
+impl PageLimit {
+    /// 从环境变量 `GITHUB_PER_PAGE`、`GITHUB_MAX_PAGES` 读取，默认每页100条，最多10页
+    pub fn from_env() -> Self {
+        let per_page = env::var("GITHUB_PER_PAGE")
+            .ok()
+            .and_then(|per_page| per_page.parse::<u8>().ok())
+            .unwrap_or(100)
+            .clamp(1, 100);
+        let max_pages = env::var("GITHUB_MAX_PAGES")
+            .ok()
+            .and_then(|max_pages| max_pages.parse::<usize>().ok())
+            .unwrap_or(10)
+            .max(1);
 
-pub fn get_first_deepseek_response(
-    response: ChatResponse<ChatCompletion, ChatCompletionStream<JSONChoiceStream>>
-) -> Result<String> {
+        Self { per_page, max_pages }
+    }
+}
 
-    let response = response.must_response();
-    info!("{:?}", response);
+/// 从第一页开始，按 `next` 链接读取后续页面
+///
+/// `is_last` 根据当前页的数据判断是否还需要下一页，例如按时间倒序时已经超出时间范围
+pub async fn collect_pages<T: DeserializeOwned>(
+    spider: &Octocrab,
+    mut page: Page<T>,
+    limit: &PageLimit,
+    is_last: impl Fn(&[T]) -> bool,
+) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    let mut page_count = 1;
+
+    loop {
+        let is_last_page = is_last(&page.items);
+        items.append(&mut page.take_items());
+
+        if is_last_page || page.next.is_none() {
+            break;
+        }
 
-    if let Some(choice) = response.choices.first() {
-        if let Some(message) = &choice.message {
-            if !message.content.is_empty() {
-                Ok(message.content.clone())
-            } else {
-                anyhow::bail!("文本为空");
-            }
-        } else {
-            anyhow::bail!("获取text失败");
+        if page_count >= limit.max_pages {
+            warn!("已读取 {} 页，达到分页上限，剩余数据被忽略", page_count);
+            break;
         }
-    } else {
-        anyhow::bail!("获取choices失败");
+
+        let Some(next_page) = spider.get_page::<T>(&page.next).await? else {
+            break;
+        };
+        page = next_page;
+        page_count += 1;
+    }
+
+    Ok(items)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer, get, web};
+    use octocrab::{Octocrab, Page};
+    use serde_json::json;
+
+    use crate::tasks::github_task::{PageLimit, collect_pages};
+
+    // 模拟 GitHub 的分页接口，共3页，每页2条
+    #[get("/items")]
+    async fn items(request: HttpRequest, query: web::Query<std::collections::HashMap<String, u32>>) -> HttpResponse {
+        let page = query.get("page").copied().unwrap_or(1);
+        let host = request.connection_info().host().to_string();
+
+        let mut res = HttpResponse::Ok();
+        if page < 3 {
+            res.insert_header(("Link", format!("<http://{}/items?page={}>; rel=\"next\"", host, page + 1)));
+        }
+        res.json(json!([page * 10 + 1, page * 10 + 2]))
+    }
+
+    async fn collect(limit: PageLimit, is_last: impl Fn(&[u32]) -> bool) -> Vec<u32> {
+        let server = HttpServer::new(|| App::new().service(items))
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let spider = Octocrab::builder()
+            .base_uri(format!("http://{}", addr))
+            .unwrap()
+            .build()
+            .unwrap();
+        let first_page: Page<u32> = spider.get("/items", None::<&()>).await.unwrap();
+
+        collect_pages(&spider, first_page, &limit, is_last).await.unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_collect_pages() {
+        let limit = PageLimit { per_page: 2, max_pages: 10 };
+        assert_eq!(collect(limit, |_| false).await, vec![11, 12, 21, 22, 31, 32]);
+
+        // 达到分页上限
+        let limit = PageLimit { per_page: 2, max_pages: 2 };
+        assert_eq!(collect(limit, |_| false).await, vec![11, 12, 21, 22]);
+
+        // 提前结束
+        let limit = PageLimit { per_page: 2, max_pages: 10 };
+        assert_eq!(collect(limit, |page| page.contains(&12)).await, vec![11, 12]);
     }
 }
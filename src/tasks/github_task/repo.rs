@@ -0,0 +1,251 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::Result;
+use chrono::Utc;
+use log::info;
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::Value;
+
+use crate::{
+    AppState,
+    bots::publisher::{CHANNEL_PUBLISHERS, SummaryKind},
+    tasks::task_run::TaskName,
+};
+
+/// 首次启动时写入的仓库，`job_schedule` 中没有指定仓库的任务使用该仓库
+pub const DEFAULT_REPO: &str = "bevyengine/bevy";
+
+/// 监听的 GitHub 仓库
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GithubRepo {
+    pub owner: String,
+    pub name: String,
+    /// 发布渠道 -> 总结类型 -> 频道，频道为 QQ 子频道ID、Discord Webhook 地址或 Telegram chat_id。
+    /// QQ 没有配置的类型发布到环境变量中的频道，其他仓库的 Discord 和 Telegram 只发布到这里配置的频道
+    pub channels: HashMap<String, HashMap<SummaryKind, String>>,
+}
+
+impl GithubRepo {
+    /// 解析 `owner/repo`
+    pub fn parse(full_name: &str) -> Result<Self> {
+        let Some((owner, name)) = full_name.split_once('/') else {
+            anyhow::bail!("仓库名称格式错误: {}", full_name);
+        };
+        if owner.is_empty() || name.is_empty() || name.contains('/') {
+            anyhow::bail!("仓库名称格式错误: {}", full_name);
+        }
+
+        Ok(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            channels: HashMap::new(),
+        })
+    }
+
+    pub fn from_model(model: &entity::watched_repo::Model) -> Result<Self> {
+        Ok(Self {
+            channels: parse_channels(&model.channels)?,
+            ..Self::parse(&model.full_name)?
+        })
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// 发布渠道配置的频道，没有配置时为空
+    pub fn channels_of(&self, publisher: &str) -> HashMap<SummaryKind, String> {
+        self.channels.get(publisher).cloned().unwrap_or_default()
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.full_name() == DEFAULT_REPO
+    }
+
+    /// 版本发布标题中的项目名称
+    pub fn project_name(&self) -> &str {
+        if self.is_default() { "Bevy" } else { &self.name }
+    }
+
+    /// 每日总结标题中的名称，其他仓库加上仓库名称
+    pub fn daily_name(&self, name: &str) -> String {
+        if self.is_default() {
+            name.to_string()
+        } else {
+            format!("{} {}", self.name, name)
+        }
+    }
+
+    /// 任务在运行记录和游标中使用的名称，默认仓库直接使用任务名称，保持和以前一致
+    pub fn job_name(&self, task: TaskName) -> String {
+        if self.is_default() {
+            task.as_str().to_string()
+        } else {
+            format!("{}@{}", task.as_str(), self.full_name())
+        }
+    }
+}
+
+/// 解析频道配置，例如 `{"qq": {"issues": "719710382"}, "telegram": {"issues": "-1001234567890"}}`，
+/// 旧格式 `{"issues": "719710382"}` 视为 QQ 子频道
+pub fn parse_channels(channels: &str) -> Result<HashMap<String, HashMap<SummaryKind, String>>> {
+    let channels: HashMap<String, Value> = serde_json::from_str(channels)?;
+
+    let mut parsed: HashMap<String, HashMap<SummaryKind, String>> = HashMap::new();
+    for (key, value) in channels {
+        match value {
+            Value::String(channel) => {
+                parsed.entry("qq".to_string()).or_default().insert(SummaryKind::from_str(&key)?, channel);
+            }
+            Value::Object(kinds) => {
+                if !CHANNEL_PUBLISHERS.contains(&key.as_str()) {
+                    anyhow::bail!("发布渠道不支持指定频道: {}", key);
+                }
+
+                let publisher_channels = parsed.entry(key.clone()).or_default();
+                for (kind, channel) in kinds {
+                    let Value::String(channel) = channel else {
+                        anyhow::bail!("频道配置错误: {}.{}", key, kind);
+                    };
+                    publisher_channels.insert(SummaryKind::from_str(&kind)?, channel);
+                }
+            }
+            _ => anyhow::bail!("频道配置错误: {}", key),
+        }
+    }
+
+    if parsed.values().flat_map(HashMap::values).any(String::is_empty) {
+        anyhow::bail!("频道不能为空");
+    }
+
+    Ok(parsed)
+}
+
+/// 读取仓库配置，没有指定时使用默认仓库
+pub async fn load_repo(app_state: &AppState, full_name: Option<&str>) -> Result<GithubRepo> {
+    let full_name = full_name.unwrap_or(DEFAULT_REPO);
+
+    let model = entity::watched_repo::Entity::find()
+        .filter(entity::watched_repo::Column::FullName.eq(full_name))
+        .one(&app_state.mysql)
+        .await?;
+
+    match model {
+        Some(model) if model.enabled => GithubRepo::from_model(&model),
+        Some(_) => anyhow::bail!("仓库已停用: {}", full_name),
+        None if full_name == DEFAULT_REPO => GithubRepo::parse(DEFAULT_REPO),
+        None => anyhow::bail!("仓库未配置: {}", full_name),
+    }
+}
+
+/// 已启用的仓库，Webhook 只处理这些仓库的事件
+pub async fn enabled_repo_names(app_state: &AppState) -> Result<Vec<String>> {
+    let names = entity::watched_repo::Entity::find()
+        .filter(entity::watched_repo::Column::Enabled.eq(true))
+        .all(&app_state.mysql)
+        .await?
+        .into_iter()
+        .map(|model| model.full_name)
+        .collect();
+
+    Ok(names)
+}
+
+/// 已停用的仓库，这些仓库的定时任务不会触发
+pub async fn disabled_repo_names(app_state: &AppState) -> Result<Vec<String>> {
+    let names = entity::watched_repo::Entity::find()
+        .filter(entity::watched_repo::Column::Enabled.eq(false))
+        .all(&app_state.mysql)
+        .await?
+        .into_iter()
+        .map(|model| model.full_name)
+        .collect();
+
+    Ok(names)
+}
+
+/// 表为空时写入默认仓库，频道使用环境变量中的配置
+pub async fn init_watched_repos(app_state: &AppState) -> Result<()> {
+    let count = entity::watched_repo::Entity::find()
+        .count(&app_state.mysql)
+        .await?;
+
+    if count > 0 {
+        return Ok(());
+    }
+
+    let new_repo = entity::watched_repo::ActiveModel {
+        id: NotSet,
+        full_name: Set(DEFAULT_REPO.to_string()),
+        channels: Set("{}".to_string()),
+        enabled: Set(true),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    new_repo.insert(&app_state.mysql).await?;
+
+    info!("已写入默认仓库: {}", DEFAULT_REPO);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bots::publisher::SummaryKind,
+        tasks::{
+            github_task::repo::{GithubRepo, parse_channels},
+            task_run::TaskName,
+        },
+    };
+
+    #[test]
+    fn test_parse_repo() {
+        let repo = GithubRepo::parse("bevyengine/bevy").unwrap();
+        assert!(repo.is_default());
+        assert_eq!(repo.project_name(), "Bevy");
+        assert_eq!(repo.daily_name("Issues"), "Issues");
+        assert_eq!(repo.job_name(TaskName::Issues), "issues");
+
+        let repo = GithubRepo::parse("bevyengine/bevy-website").unwrap();
+        assert_eq!((repo.owner.as_str(), repo.name.as_str()), ("bevyengine", "bevy-website"));
+        assert!(!repo.is_default());
+        assert_eq!(repo.project_name(), "bevy-website");
+        assert_eq!(repo.daily_name("PRs"), "bevy-website PRs");
+        assert_eq!(repo.job_name(TaskName::Prs), "prs@bevyengine/bevy-website");
+
+        assert!(GithubRepo::parse("bevy").is_err());
+        assert!(GithubRepo::parse("bevyengine/").is_err());
+        assert!(GithubRepo::parse("a/b/c").is_err());
+    }
+
+    #[test]
+    fn test_parse_channels() {
+        // 旧格式只有 QQ 子频道
+        let channels = parse_channels(r#"{"issues": "719710382", "release": "719761823"}"#).unwrap();
+        let qq = &channels["qq"];
+        assert_eq!(qq.get(&SummaryKind::Issues).map(String::as_str), Some("719710382"));
+        assert_eq!(qq.get(&SummaryKind::Release).map(String::as_str), Some("719761823"));
+        assert_eq!(channels.len(), 1);
+
+        let channels = parse_channels(
+            r#"{"qq": {"issues": "719710382"}, "discord": {"prs": "https://discord.com/api/webhooks/1/a"}, "telegram": {"issues": "-100123"}}"#,
+        )
+        .unwrap();
+        assert_eq!(channels["qq"].get(&SummaryKind::Issues).map(String::as_str), Some("719710382"));
+        assert_eq!(
+            channels["discord"].get(&SummaryKind::Prs).map(String::as_str),
+            Some("https://discord.com/api/webhooks/1/a")
+        );
+        assert_eq!(channels["telegram"].get(&SummaryKind::Issues).map(String::as_str), Some("-100123"));
+
+        let repo = GithubRepo { channels, ..GithubRepo::parse("bevyengine/bevy-website").unwrap() };
+        assert_eq!(repo.channels_of("discord").len(), 1);
+        assert!(repo.channels_of("feed").is_empty());
+
+        assert!(parse_channels("{}").unwrap().is_empty());
+        assert!(parse_channels(r#"{"bsky": "1"}"#).is_err());
+        assert!(parse_channels(r#"{"feed": {"issues": "1"}}"#).is_err());
+        assert!(parse_channels(r#"{"discord": {"issues": ""}}"#).is_err());
+        assert!(parse_channels(r#"{"discord": {"issues": 1}}"#).is_err());
+    }
+}
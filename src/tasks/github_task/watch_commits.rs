@@ -1,103 +1,118 @@
+use std::time::Instant;
+
 use crate::{
+    AppState,
     bots::{
-        deepseek_client::build_deepseek_client, github_client::build_github_client,
-        qqbot_client::QQBotClient,
+        github_client::build_github_client, llm_client::build_llm_provider,
+        publisher::{Summary, SummaryKind, build_repo_publishers},
+    },
+    tasks::{
+        github_task::{PageLimit, collect_pages, repo::GithubRepo},
+        glossary::load_glossary,
+        map_reduce::{MapReducePrompt, map_reduce},
+        prompt_template::{PromptVars, load_template},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, TaskStats},
+        time_window::TimeWindow,
     },
-    tasks::github_task::{BEVY_OWNER, BEVY_REPO, get_first_deepseek_response},
 };
-use actix_rt::spawn;
 use anyhow::Result;
-use chrono::{Days, Local};
-use deepseek_api::{
-    CompletionsRequestBuilder, RequestBuilder, request::MessageRequest,
-    response::ModelType::DeepSeekReasoner,
-};
-use log::{error, info};
-use tokio_schedule::{Job, every};
-
-pub fn get_new_commits() -> Result<()> {
-    info!("开始定时抓取commits任务");
-
-    // let mut now = Local::now().to_utc();
-
-    let every_day_task = every(1).day().at(12, 00, 00)
-        .perform(|| async {
-            if let Err(err) = run_commits_task().await {
-                error!("{err:?}");
-            }
-        });
-
-    spawn(every_day_task);
-
-    Ok(())
+use log::info;
+use octocrab::models::repos::RepoCommit;
+
+/// 文件列表只保留文件名和增删行数，完整的 diff 信息会占满上下文
+fn format_commit(commit: &RepoCommit) -> String {
+    let files = commit
+        .files
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|file| format!("{}(+{} -{})", file.filename, file.additions, file.deletions))
+        .collect::<Vec<_>>();
+
+    format!(
+        "Commit Message内容: {:?}，发布者名称：{}, 时间UTC: {:?}, 文件更改列表：{}, 原文链接: {}",
+        commit.commit.message,
+        commit.author.as_ref().map(|author| author.login.as_str()).unwrap_or("unknown"),
+        commit.commit.committer.as_ref().and_then(|committer| committer.date),
+        if files.is_empty() { "无".to_string() } else { files.join(", ") },
+        commit.html_url
+    )
 }
 
-
-pub async fn run_commits_task() -> Result<()> {
+pub async fn run_commits_task(app_state: AppState, repo: GithubRepo, window: TimeWindow) -> Result<TaskStats> {
     let spider = build_github_client()?;
 
-    let since = Local::now().to_utc().checked_sub_days(Days::new(1)).unwrap();
+    let limit = PageLimit::from_env();
 
-    let issue_list = spider
-        .repos(BEVY_OWNER, BEVY_REPO)
+    let first_page = spider
+        .repos(&repo.owner, &repo.name)
         .list_commits()
-        .since(since)
+        .since(window.since)
+        .until(window.until)
+        .per_page(limit.per_page)
         .send()
         .await?;
 
-    if issue_list.items.is_empty() {
-        anyhow::bail!("今日Commits为空");
+    let mut issue_list = collect_pages(&spider, first_page, &limit, |_| false).await?;
+
+    // until 参数包含边界时间，这里按 [since, until) 再筛选一次
+    issue_list.retain(|commit| {
+        commit.commit.committer
+            .as_ref()
+            .and_then(|committer| committer.date)
+            .is_none_or(|date| window.contains(date))
+    });
+
+    // 没有数据时也算成功，游标照常推进
+    if issue_list.is_empty() {
+        info!("时间范围内没有Commit: {:?}", window);
+        return Ok(TaskStats::default());
     }
 
+    let items = issue_list.len();
+    let links = issue_list
+        .iter()
+        .map(|commit| commit.html_url.clone())
+        .collect::<Vec<_>>();
+    let source_ids = issue_list
+        .iter()
+        .map(|commit| commit.sha.clone())
+        .collect::<Vec<_>>();
+
     // 发送到AI进行总结
-    let deepseek_client = build_deepseek_client()?;
-
-    let mut all_issue = issue_list.into_iter().map(|issue| {
-        MessageRequest::user(
-            &format!("Commit Message内容: {:?}，发布者名称：{:?}, 文件更改列表：{:?}, 原文链接: {}",
-            issue.commit.message, issue.author, issue.files, issue.html_url)
-        )
-    }).collect::<Vec<_>>();
-
-    all_issue.push(
-        MessageRequest::user(
-            "你是一个Bevy游戏引擎的社区宣传工作者，你需要根据用户提供的每日的Commits列表信息进行分类总结，总结中需要包含Commits的标题，内容，发布者名称，时间UTC，状态，原文链接，并进行翻译，对其中的游戏引擎底层原理、图形学等专业知识（术语）进行恰当的解释，挑选比较难的，常用的、都比较了解Commit略过。
-            示例Commit：
-            假设一个Commit：
-
-            标题: Fix memory leak in ECS system
-            内容: There is a memory leak when entities are despawned in the ECS. This causes the game to crash after prolonged play.
-            发布者: john_doe
-            时间: 2023-10-05T12:00:00Z
-            链接: https://github.com/bevyengine/bevy/commit/2facb2572d84e9b9923edef7f35bae2c26308081
-
-            总结：
-            标题: 修复ECS系统中的内存泄漏（翻译）
-            内容: 当实体在ECS中被销毁时，存在内存泄漏问题，导致游戏在长时间运行后崩溃。（翻译和详细解释）
-            发布者: john_doe
-            时间: 2023-10-05 12:00:00 UTC
-            状态: 开启
-            链接:  [GitHub Commit #xxxx](原文链接)
-            术语解释: ECS（Entity-Component-System）是一种游戏开发架构，用于管理游戏对象（实体）及其属性（组件）和行为（系统）。内存泄漏是指程序在分配内存后未能释放，导致内存使用不断增加。
-            对于多个Commit，可以列出列表。"
-        )
-    );
-
-    let res = CompletionsRequestBuilder::new(&all_issue)
-        .use_model(DeepSeekReasoner)
-        .stream(false)
-        .do_request(&deepseek_client)
-        .await?;
+    let llm = build_llm_provider(TaskName::Commits.as_str())?;
+
+    let all_issue = issue_list.iter().map(format_commit).collect::<Vec<_>>();
+
+    let map_template = load_template(&app_state, "commits").await?;
+    let reduce_template = load_template(&app_state, "commits-reduce").await?;
+    let vars = PromptVars::new(window.date(), &repo.full_name(), all_issue.len());
+    let map_prompt = map_template.render(&vars);
+    let reduce_prompt = reduce_template.render(&vars);
+    let prompt = MapReducePrompt { map: &map_prompt, reduce: &reduce_prompt, context: None };
+    let glossary = load_glossary(&app_state).await;
 
-    let text = get_first_deepseek_response(res)?;
+    let now = Instant::now();
+    let output = map_reduce(llm.as_ref(), &repo.daily_name("Commits"), &prompt, &glossary, &all_issue, None).await?;
+    let llm_latency = now.elapsed();
+    info!("AI总结完成, 耗时: {}秒, Token: {}", llm_latency.as_secs_f32(), output.usage.total_tokens);
 
-    if !text.is_empty() {
+    if !output.text.is_empty() {
         // 发送到频道
-        let qq_client = QQBotClient::new_with_default(false).await?;
-        qq_client.send_commit_summary("Commits", &text).await?;
+        let publishers = build_repo_publishers(&app_state, &repo).await?;
+        let summary = Summary::daily(SummaryKind::Commits, &repo.daily_name("Commits"), window.date(), &output.text)
+            .with_links(links);
+        let provenance = Provenance {
+            source_ids,
+            prompt_version: format!("{},{}", map_template.version_tag(), reduce_template.version_tag()),
+            model: output.model.clone(),
+            raw_output: output.text,
+        };
+        publish_and_record(&app_state, &publishers, &summary, &provenance).await?;
     }
 
-    Ok(())
+    Ok(TaskStats::new(items, llm_latency).with_model(&output.model))
 }
 
 
@@ -105,7 +120,7 @@ pub async fn run_commits_task() -> Result<()> {
 mod tests {
     use dotenvy::dotenv;
 
-    use crate::tasks::github_task::{BEVY_REPO, BEVY_OWNER};
+    use crate::tasks::github_task::repo::{DEFAULT_REPO, GithubRepo};
 
     #[tokio::test]
     async fn test_issue_list() {
@@ -121,8 +136,9 @@ mod tests {
             .build()
             .unwrap();
 
+        let repo = GithubRepo::parse(DEFAULT_REPO).unwrap();
         let issue_list = spider
-            .issues(BEVY_OWNER, BEVY_REPO)
+            .issues(&repo.owner, &repo.name)
             .list()
             // .since(since)
             .page(0_u32)
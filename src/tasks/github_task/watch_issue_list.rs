@@ -1,164 +1,332 @@
-use actix_rt::spawn;
+use std::collections::HashSet;
+
 use anyhow::Result;
-use chrono::{Days, Local};
-use deepseek_api::{
-    CompletionsRequestBuilder, RequestBuilder, request::MessageRequest, response::AssistantMessage,
-};
+use chrono::{DateTime, NaiveDate, Utc};
 use log::{error, info};
-use tokio_schedule::{Job, every};
+use octocrab::{Octocrab, models::issues::{Comment, Issue}};
+use url::Url;
 
 use crate::{
+    AppState,
     bots::{
-        deepseek_client::build_deepseek_client, github_client::build_github_client,
-        qqbot_client::QQBotClient,
+        github_client::build_github_client, llm_client::build_llm_provider,
+        publisher::{Summary, SummaryKind, build_repo_publishers},
+    },
+    tasks::{
+        github_task::{
+            PageLimit, collect_pages,
+            label_group::{DigestItem, TranslateGroup, group_by_area, translate_groups},
+            repo::GithubRepo,
+        },
+        glossary::load_glossary,
+        prompt_template::{PromptVars, load_template},
+        subscription::{SubscriptionItem, route_subscriptions},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, TaskStats},
+        time_window::TimeWindow,
     },
-    tasks::github_task::{BEVY_OWNER, BEVY_REPO},
 };
 
-pub fn get_new_issues() -> Result<()> {
-    info!("开始定时抓取issue任务");
+/// Issue 在时间范围内发生的变化，决定在总结中的分组
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSection {
+    Opened,
+    Closed,
+    Commented,
+}
 
-    // let mut now = Local::now().to_utc();
+impl IssueSection {
+    pub const ALL: [IssueSection; 3] = [
+        IssueSection::Opened,
+        IssueSection::Closed,
+        IssueSection::Commented,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            IssueSection::Opened => "新开的Issue",
+            IssueSection::Closed => "已关闭的Issue",
+            IssueSection::Commented => "有新评论的Issue",
+        }
+    }
 
-    let every_day_task = every(1).day().at(12, 00, 00).perform(|| async {
-        match run_issue_async_task().await {
-            Ok(_) => (),
-            Err(err) => {
-                error!("{err:?}");
-            }
+    /// 时间范围内新开又关闭的归为已关闭，只修改了标签等信息、没有新评论的不参与总结
+    ///
+    /// `has_new_comment` 为时间范围内是否有新评论，旧评论不算
+    pub fn classify(
+        created_at: DateTime<Utc>,
+        closed_at: Option<DateTime<Utc>>,
+        has_new_comment: bool,
+        window: &TimeWindow,
+    ) -> Option<Self> {
+        if closed_at.is_some_and(|closed_at| window.contains(closed_at)) {
+            Some(IssueSection::Closed)
+        } else if window.contains(created_at) {
+            Some(IssueSection::Opened)
+        } else if has_new_comment {
+            Some(IssueSection::Commented)
+        } else {
+            None
         }
-    });
+    }
+}
+
+fn format_issue(issue: &Issue) -> String {
+    format!(
+        "标题: {}, 内容: {:?}，发布者名称：{:?}, 时间UTC: {}, 状态: {:?}, 关闭时间UTC: {:?}, 评论数: {}, 标签: {}， 原文链接: {}",
+        issue.title,
+        issue.body,
+        issue.user.name,
+        issue.created_at,
+        issue.state,
+        issue.closed_at,
+        issue.comments,
+        issue.labels.iter().map(|label| label.name.as_str()).collect::<Vec<_>>().join(", "),
+        issue.html_url
+    )
+}
+
+/// 评论所属的 Issue 或 PR 编号，`issue_url` 形如 `https://api.github.com/repos/{owner}/{repo}/issues/{number}`
+fn issue_number_from_url(issue_url: &Url) -> Option<u64> {
+    issue_url.path_segments()?.next_back()?.parse().ok()
+}
 
-    spawn(every_day_task);
+/// 时间范围内有新评论的 Issue 编号
+///
+/// 评论接口的 `since` 按更新时间筛选，修改旧评论也会返回，需要再按创建时间筛选
+async fn get_commented_issues(
+    spider: &Octocrab,
+    repo: &GithubRepo,
+    window: &TimeWindow,
+    limit: &PageLimit,
+) -> Result<HashSet<u64>> {
+    let first_page = spider
+        .issues(&repo.owner, &repo.name)
+        .list_issue_comments()
+        .since(window.since)
+        .per_page(limit.per_page)
+        .send()
+        .await?;
+
+    let comments = collect_pages(spider, first_page, limit, |_| false).await?;
+
+    Ok(comments
+        .iter()
+        .filter(|comment| window.contains(comment.created_at))
+        .filter_map(|comment: &Comment| issue_number_from_url(comment.issue_url.as_ref()?))
+        .collect())
+}
+
+/// 按代码确定的分组拼接总结，`layout` 和 `texts` 一一对应
+fn render_digest(project: &str, date: NaiveDate, layout: &[(IssueSection, String, usize)], texts: &[String]) -> String {
+    let section_count = |section: IssueSection| -> usize {
+        layout
+            .iter()
+            .filter(|(group_section, _, _)| *group_section == section)
+            .map(|(_, _, count)| count)
+            .sum()
+    };
+
+    let mut text = format!(
+        "每日{} Issue总结\n总结日期: {}\n统计: 新开{}个，已关闭{}个，有新评论{}个\n",
+        project,
+        date.format("%Y年%m月%d日"),
+        section_count(IssueSection::Opened),
+        section_count(IssueSection::Closed),
+        section_count(IssueSection::Commented)
+    );
+
+    let mut last_section = None;
+    for ((section, area, count), group_text) in layout.iter().zip(texts) {
+        if last_section != Some(*section) {
+            text.push_str(&format!("\n## {}（{}个）\n", section.title(), section_count(*section)));
+            last_section = Some(*section);
+        }
+        text.push_str(&format!("\n### {}（{}个）\n{}\n", area, count, group_text.trim()));
+    }
 
-    Ok(())
+    text
 }
 
-pub async fn run_issue_async_task() -> Result<()> {
+pub async fn run_issue_async_task(app_state: AppState, repo: GithubRepo, window: TimeWindow) -> Result<TaskStats> {
     info!("开始任务");
 
     let spider = build_github_client()?;
 
-    let since = Local::now()
-        .to_utc()
-        .checked_sub_days(Days::new(1))
-        .unwrap();
+    let limit = PageLimit::from_env();
 
-    let issue_list = spider
-        .issues(BEVY_OWNER, BEVY_REPO)
+    let first_page = spider
+        .issues(&repo.owner, &repo.name)
         .list()
-        .since(since)
+        .state(octocrab::params::State::All)
+        .since(window.since)
+        .per_page(limit.per_page)
         .send()
         .await?;
 
-    if issue_list.items.is_empty() {
-        anyhow::bail!("今日Issues为空");
-    }
+    let issue_list = collect_pages(&spider, first_page, &limit, |_| false).await?;
+    let commented_issues = get_commented_issues(&spider, &repo, &window, &limit).await?;
 
-    let issue_main_message = issue_list
+    // Issue 接口也会返回 PR，PR 在 PR 总结中处理
+    // 补跑过去的时间段时，Issue 在结束时间之后可能又有更新，所以不按 updated_at 筛选结束时间，
+    // 由创建、关闭、评论时间是否在时间范围内决定
+    let issue_list = issue_list
         .into_iter()
-        .map(|issue| {
-            format!(
-                "标题: {}, 内容: {:?}，发布者名称：{:?}, 时间UTC: {}, 状态: {:?}， 原文链接: {}",
-                issue.title,
-                issue.body,
-                issue.user.name,
-                issue.created_at,
-                issue.state,
-                issue.html_url.to_string()
-            )
+        .filter(|issue| issue.pull_request.is_none())
+        .filter_map(|issue| {
+            let has_new_comment = commented_issues.contains(&issue.number);
+            IssueSection::classify(issue.created_at, issue.closed_at, has_new_comment, &window)
+                .map(|section| (section, issue))
         })
         .collect::<Vec<_>>();
 
-    // 写入到issue.json 文件
-    let issue_json = serde_json::to_string(&issue_main_message)?;
-    std::fs::write("issue.json", issue_json)?;
-
-    // 发送到AI进行总结
-    let deepseek_client = build_deepseek_client()?;
-
-    let mut chat_messages = vec![];
-    chat_messages.push(
-        MessageRequest::Assistant(AssistantMessage::new(
-            r"你是一个Bevy游戏引擎的社区宣传工作者，你需要根据用户提供的每日的issue列表信息进行分类总结，总结中需要包含issue的标题，内容，发布者名称，时间UTC，状态，原文链接，并进行翻译，对其中的游戏引擎底层原理、图形学等专业知识（术语）进行恰当的解释，挑选比较难的，常用的、都比较了解的略过。
-            示例issue：
-            假设一个issue：
-
-            标题: Fix memory leak in ECS system
-            内容: There is a memory leak when entities are despawned in the ECS. This causes the game to crash after prolonged play.
-            发布者: john_doe
-            时间: 2023-10-05T12:00:00Z
-            状态: open
-            链接: https://github.com/bevyengine/bevy/issues/1234
-
-            总结：
-            标题: 修复ECS系统中的内存泄漏（翻译）
-            内容: 当实体在ECS中被销毁时，存在内存泄漏问题，导致游戏在长时间运行后崩溃。（翻译和详细解释）
-            发布者: john_doe
-            时间: 2023-10-05 12:00:00 UTC
-            状态: 开启
-            链接:  [GitHub Issue #xxxx](原文链接)
-            术语解释: ECS（Entity-Component-System）是一种游戏开发架构，用于管理游戏对象（实体）及其属性（组件）和行为（系统）。内存泄漏是指程序在分配内存后未能释放，导致内存使用不断增加。
-            对于多个issue，可以列出列表。
-
-            最终响应结构：
-                每日Bevy Issue总结
-                    总结日期: {{}}年{{}}月{{}}日
-                    统计: 共{{}}个issue,每类多少个。
-                详细报告：以类别为一个标题开始罗列每个issue的详细信息。分类可以加上Unicode图标。",
-        ))
-    );
-    chat_messages.push(MessageRequest::user(&issue_main_message.join("\n")));
-
-    info!("开始请求AI总结");
+    // 没有数据时也算成功，游标照常推进
+    if issue_list.is_empty() {
+        info!("时间范围内没有Issue: {:?}", window);
+        return Ok(TaskStats::default());
+    }
 
-    let res = CompletionsRequestBuilder::new(&chat_messages)
-        .use_model(deepseek_api::response::ModelType::DeepSeekReasoner)
-        .stream(false)
-        .max_tokens(8192)
-        .unwrap()
-        .do_request(&deepseek_client)
-        .await;
+    let items = issue_list.len();
+    let links = issue_list
+        .iter()
+        .map(|(_, issue)| issue.html_url.to_string())
+        .collect::<Vec<_>>();
+    let source_ids = issue_list
+        .iter()
+        .map(|(_, issue)| issue.number.to_string())
+        .collect::<Vec<_>>();
 
-    info!("AI总结完成");
+    // 分组由代码根据标签确定，AI 只负责每组的翻译和解释
+    let mut groups = vec![];
+    let mut layout = vec![];
+    for section in IssueSection::ALL {
+        let section_items = issue_list
+            .iter()
+            .filter(|(issue_section, _)| *issue_section == section)
+            .map(|(_, issue)| DigestItem {
+                labels: issue.labels.iter().map(|label| label.name.clone()).collect(),
+                text: format_issue(issue),
+            })
+            .collect::<Vec<_>>();
+
+        for (area, group) in group_by_area(&section_items) {
+            groups.push(TranslateGroup {
+                context: format!("{} / {}", section.title(), area),
+                lines: group.iter().map(|item| item.text.clone()).collect(),
+            });
+            layout.push((section, area.to_string(), group.len()));
+        }
+    }
 
-    let Ok(res) = res else {
-        error!("请求失败");
-        return Ok(());
+    // 发送到AI进行翻译
+    let llm = build_llm_provider(TaskName::Issues.as_str())?;
+    let template = load_template(&app_state, "issues").await?;
+    let glossary = load_glossary(&app_state).await;
+
+    let translation = translate_groups(
+        llm.as_ref(),
+        &template.render(&PromptVars::new(window.date(), &repo.full_name(), items)),
+        &glossary,
+        &groups,
+    )
+    .await?;
+
+    let text = render_digest(repo.project_name(), window.date(), &layout, &translation.texts);
+
+    info!("开始发布帖子");
+    // 发送到频道
+    let publishers = build_repo_publishers(&app_state, &repo).await?;
+    let summary = Summary::daily(SummaryKind::Issues, &repo.daily_name("Issues"), window.date(), &text)
+        .with_links(links);
+    let provenance = Provenance {
+        source_ids,
+        prompt_version: template.version_tag(),
+        model: translation.model.clone(),
+        raw_output: translation.texts.join("\n\n"),
     };
-    let response = res.must_response();
-    info!("{:?}", response);
-    let Some(choice) = response.choices.first() else {
-        error!("获取choices失败");
-        return Ok(());
-    };
-    let Some(message) = &choice.message else {
-        error!("获取message失败");
-        return Ok(());
-    };
-    if !message.content.is_empty() {
-        info!("开始发布帖子");
-        // 发送到频道
-        let qq_client = QQBotClient::new_with_default(false).await?;
-        qq_client
-            .send_issue_summary("Issues", &message.content)
-            .await?;
-        info!("帖子发布完成");
-    } else {
-        error!("文本为空");
+    publish_and_record(&app_state, &publishers, &summary, &provenance).await?;
+    info!("帖子发布完成");
+
+    // 订阅发送失败不影响每日总结
+    let subscription_items = issue_list
+        .iter()
+        .map(|(_, issue)| SubscriptionItem::from_issue(issue))
+        .collect::<Vec<_>>();
+    if let Err(err) = route_subscriptions(&app_state, &repo, SummaryKind::Issues, window.date(), &subscription_items).await {
+        error!("发送订阅发生错误：{err:?}");
     }
 
-    Ok(())
+    Ok(TaskStats::new(items, translation.llm_latency).with_model(&translation.model))
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{DateTime, NaiveDate, Utc};
     use dotenvy::dotenv;
-
-    use crate::tasks::github_task::{
-        BEVY_OWNER, BEVY_REPO, watch_issue_list::run_issue_async_task,
+    use url::Url;
+
+    use crate::{
+        AppState,
+        tasks::{
+            github_task::{
+                repo::{DEFAULT_REPO, GithubRepo},
+                watch_issue_list::{IssueSection, issue_number_from_url, render_digest, run_issue_async_task},
+            },
+            time_window::TimeWindow,
+        },
     };
 
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_render_digest() {
+        let layout = vec![
+            (IssueSection::Opened, "A-ECS".to_string(), 2),
+            (IssueSection::Opened, "其他".to_string(), 1),
+            (IssueSection::Commented, "A-Rendering".to_string(), 1),
+        ];
+        let texts = vec!["ecs\n".to_string(), "other".to_string(), "rendering".to_string()];
+        let date = NaiveDate::from_ymd_opt(2025, 11, 10).unwrap();
+
+        assert_eq!(
+            render_digest("Bevy", date, &layout, &texts),
+            "每日Bevy Issue总结\n总结日期: 2025年11月10日\n统计: 新开3个，已关闭0个，有新评论1个\n\
+            \n## 新开的Issue（3个）\n\n### A-ECS（2个）\necs\n\n### 其他（1个）\nother\n\
+            \n## 有新评论的Issue（1个）\n\n### A-Rendering（1个）\nrendering\n"
+        );
+
+        // 其他仓库使用仓库名称
+        let project = GithubRepo::parse("bevyengine/bevy-website").unwrap();
+        assert!(render_digest(project.project_name(), date, &layout, &texts).starts_with("每日bevy-website Issue总结\n"));
+    }
+
+    #[test]
+    fn test_issue_section() {
+        let window = TimeWindow::parse(None, Some("2025-11-10T12:00:00Z")).unwrap();
+        let old = time("2025-11-01T00:00:00Z");
+        let new = time("2025-11-10T00:00:00Z");
+
+        assert_eq!(IssueSection::classify(new, None, false, &window), Some(IssueSection::Opened));
+        assert_eq!(IssueSection::classify(old, Some(new), true, &window), Some(IssueSection::Closed));
+        // 新开后又关闭的归为已关闭
+        assert_eq!(IssueSection::classify(new, Some(new), false, &window), Some(IssueSection::Closed));
+        assert_eq!(IssueSection::classify(old, Some(old), true, &window), Some(IssueSection::Commented));
+        assert_eq!(IssueSection::classify(old, None, true, &window), Some(IssueSection::Commented));
+        // 只有旧评论、时间范围内修改了标签的不参与总结
+        assert_eq!(IssueSection::classify(old, None, false, &window), None);
+    }
+
+    #[test]
+    fn test_issue_number_from_url() {
+        let url = Url::parse("https://api.github.com/repos/bevyengine/bevy/issues/21432").unwrap();
+        assert_eq!(issue_number_from_url(&url), Some(21432));
+
+        let url = Url::parse("https://api.github.com/repos/bevyengine/bevy/issues").unwrap();
+        assert_eq!(issue_number_from_url(&url), None);
+    }
+
     #[tokio::test]
     async fn test_issue_list() {
         println!("开始测试issue列表获取");
@@ -173,8 +341,9 @@ mod tests {
             .build()
             .unwrap();
 
+        let repo = GithubRepo::parse(DEFAULT_REPO).unwrap();
         let issue_list = spider
-            .issues(BEVY_OWNER, BEVY_REPO)
+            .issues(&repo.owner, &repo.name)
             .list()
             // .since(since)
             .page(0_u32)
@@ -195,6 +364,8 @@ mod tests {
         dotenv().ok();
         env_logger::init();
 
-        run_issue_async_task().await.unwrap();
+        let app_state = AppState::new_with_default().await;
+        let repo = GithubRepo::parse(DEFAULT_REPO).unwrap();
+        run_issue_async_task(app_state, repo, TimeWindow::last_day()).await.unwrap();
     }
 }
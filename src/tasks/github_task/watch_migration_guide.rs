@@ -0,0 +1,297 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Days, Utc};
+use log::info;
+use octocrab::{Octocrab, Page, models::{Author, Label, Milestone}};
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+use crate::{
+    AppState,
+    bots::{
+        github_client::build_github_client, llm_client::build_llm_provider,
+        publisher::{Summary, SummaryKind, build_repo_publishers},
+    },
+    tasks::{
+        github_task::{
+            PageLimit, collect_pages,
+            label_group::{Labeled, group_by_area},
+            repo::GithubRepo,
+            watch_milestones::{get_closed_milestone_list, get_milestone_list},
+        },
+        glossary::load_glossary,
+        map_reduce::{MapReducePrompt, map_reduce},
+        prompt_template::{PromptVars, load_template},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, TaskStats},
+    },
+};
+
+const MIGRATION_GUIDE_LABEL: &str = "M-Needs-Migration-Guide";
+// 只为最近关闭的里程碑生成，避免首次运行时为所有历史版本生成
+const RECENT_DAYS: u64 = 30;
+
+/// 需要写迁移指南的 PR
+#[derive(Debug, Clone)]
+pub struct MigrationItem {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub author: String,
+    pub labels: Vec<String>,
+}
+
+impl MigrationItem {
+    fn from_pull(pull: MilestonePull) -> Self {
+        Self {
+            number: pull.number,
+            title: pull.title,
+            body: pull.body,
+            html_url: pull.html_url,
+            author: pull.user.login,
+            labels: pull.labels.into_iter().map(|label| label.name).collect(),
+        }
+    }
+}
+
+/// Issue 接口返回的 PR，octocrab 的 `Issue` 没有 `pull_request.merged_at`
+#[derive(Debug, Deserialize)]
+struct MilestonePull {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    html_url: String,
+    user: Author,
+    labels: Vec<Label>,
+    pull_request: Option<PullRequestMerge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestMerge {
+    merged_at: Option<DateTime<Utc>>,
+}
+
+impl Labeled for MigrationItem {
+    fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+/// 版本标签对应的里程碑名称，例如 `v0.18.0` 对应 `0.18.0` 或 `0.18`
+pub fn milestone_titles_for_tag(tag_name: &str) -> Vec<String> {
+    let version = tag_name.trim_start_matches('v');
+
+    let mut titles = vec![version.to_string()];
+    if let Some(minor) = version.strip_suffix(".0") {
+        titles.push(minor.to_string());
+    }
+
+    titles
+}
+
+/// 为最近关闭的里程碑生成迁移指南，只用于默认仓库
+pub async fn run_migration_guide_task(app_state: AppState, repo: GithubRepo) -> Result<TaskStats> {
+    let spider = build_github_client()?;
+
+    let since = Utc::now().checked_sub_days(Days::new(RECENT_DAYS)).unwrap();
+
+    let mut stats = TaskStats::default();
+    for milestone in get_closed_milestone_list(&spider, &repo).await? {
+        if milestone.closed_at.is_none_or(|closed_at| closed_at < since) {
+            continue;
+        }
+
+        match generate_migration_guide(&app_state, &spider, &repo, &milestone).await {
+            Ok(guide_stats) => stats += guide_stats,
+            Err(err) => stats.add_failure(&format!("迁移指南 {}", milestone.title), &err),
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 版本发布时为对应的里程碑生成迁移指南
+pub async fn generate_for_release(app_state: &AppState, repo: &GithubRepo, tag_name: &str) -> Result<TaskStats> {
+    let spider = build_github_client()?;
+
+    let titles = milestone_titles_for_tag(tag_name);
+
+    let mut milestones = get_closed_milestone_list(&spider, repo).await?;
+    milestones.extend(get_milestone_list(&spider, repo).await?);
+
+    let Some(milestone) = milestones.iter().find(|milestone| titles.contains(&milestone.title)) else {
+        info!("版本 {} 没有对应的里程碑，跳过迁移指南", tag_name);
+        return Ok(TaskStats::default());
+    };
+
+    generate_migration_guide(app_state, &spider, repo, milestone).await
+}
+
+async fn generate_migration_guide(
+    app_state: &AppState,
+    spider: &Octocrab,
+    repo: &GithubRepo,
+    milestone: &Milestone,
+) -> Result<TaskStats> {
+    let exist = entity::migration_guide::Entity::find()
+        .filter(entity::migration_guide::Column::Milestone.eq(&milestone.title))
+        .one(&app_state.mysql)
+        .await?;
+
+    if exist.is_some() {
+        info!("里程碑 {} 已经生成过迁移指南，跳过处理", milestone.title);
+        return Ok(TaskStats::default());
+    }
+
+    let items = get_merged_migration_prs(spider, repo, milestone.number).await?;
+
+    if items.is_empty() {
+        info!("里程碑 {} 没有需要迁移指南的PR", milestone.title);
+        return Ok(TaskStats::default());
+    }
+
+    let groups = group_by_area(&items);
+
+    let llm = build_llm_provider(TaskName::MigrationGuide.as_str())?;
+    let map_template = load_template(app_state, "migration-guide").await?;
+    let reduce_template = load_template(app_state, "migration-guide-reduce").await?;
+    let vars = PromptVars::today(&repo.full_name(), items.len());
+    let map_prompt = map_template.render(&vars);
+    let reduce_prompt = reduce_template.render(&vars);
+    // PR 较多时分批生成，每批都带上版本
+    let version = format!("版本: {}", milestone.title);
+    let prompt = MapReducePrompt { map: &map_prompt, reduce: &reduce_prompt, context: Some(&version) };
+    let glossary = load_glossary(app_state).await;
+
+    let now = Instant::now();
+    info!("开始请求AI生成迁移指南: {}, 后端: {}", milestone.title, llm.name());
+
+    let output = map_reduce(llm.as_ref(), &milestone.title, &prompt, &glossary, &guide_items(&items), Some(8192)).await?;
+
+    let llm_latency = now.elapsed();
+    info!("AI生成迁移指南完成, 耗时: {}秒, Token: {}", llm_latency.as_secs_f32(), output.usage.total_tokens);
+
+    // 发布到对应版本的里程碑子频道
+    let links = items.iter().map(|item| item.html_url.clone()).collect::<Vec<_>>();
+    let pr_numbers = items.iter().map(|item| item.number.to_string()).collect::<Vec<_>>();
+    let areas = groups.iter().map(|(area, _)| *area).collect::<Vec<_>>();
+
+    let publishers = build_repo_publishers(app_state, repo).await?;
+    let title = format!("Bevy {} 迁移指南", milestone.title);
+    let summary = Summary::new(SummaryKind::Milestone, &title, &output.text)
+        .with_links(links)
+        .with_topic(&milestone.title);
+    let provenance = Provenance {
+        source_ids: pr_numbers.clone(),
+        prompt_version: format!("{},{}", map_template.version_tag(), reduce_template.version_tag()),
+        model: output.model.clone(),
+        raw_output: output.text.clone(),
+    };
+    publish_and_record(app_state, &publishers, &summary, &provenance).await?;
+
+    let new_guide = entity::migration_guide::ActiveModel {
+        id: NotSet,
+        milestone: Set(milestone.title.clone()),
+        pr_numbers: Set(serde_json::to_string(&pr_numbers)?),
+        areas: Set(serde_json::to_string(&areas)?),
+        content: Set(output.text),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+    new_guide.insert(&app_state.mysql).await?;
+
+    Ok(TaskStats::new(items.len(), llm_latency).with_model(&output.model))
+}
+
+/// 里程碑中已合并、需要迁移指南的 PR，合并状态直接从 Issue 接口返回的 `pull_request.merged_at` 判断
+async fn get_merged_migration_prs(spider: &Octocrab, repo: &GithubRepo, milestone_id: i64) -> Result<Vec<MigrationItem>> {
+    let limit = PageLimit::from_env();
+    let first_page: Page<MilestonePull> = spider
+        .get(
+            format!("/repos/{}/{}/issues", repo.owner, repo.name),
+            Some(&[
+                ("milestone", milestone_id.to_string()),
+                ("labels", MIGRATION_GUIDE_LABEL.to_string()),
+                ("state", "closed".to_string()),
+                ("per_page", limit.per_page.to_string()),
+            ])
+        )
+        .await?;
+
+    let pulls = collect_pages(spider, first_page, &limit, |_| false).await?;
+
+    Ok(pulls
+        .into_iter()
+        .filter(|pull| pull.pull_request.as_ref().is_some_and(|pr| pr.merged_at.is_some()))
+        .map(MigrationItem::from_pull)
+        .collect())
+}
+
+/// 按领域分组后每个 PR 一条，分批时每条都带上所在的领域
+pub fn guide_items(items: &[MigrationItem]) -> Vec<String> {
+    group_by_area(items)
+        .into_iter()
+        .flat_map(|(area, group)| {
+            group.into_iter().map(move |item| format!(
+                "## {}\nPR #{} 标题: {}, 作者: {}, 原文链接: {}, 内容: {:?}",
+                area, item.number, item.title, item.author, item.html_url, item.body
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::github_task::{
+        label_group::group_by_area,
+        watch_migration_guide::{MigrationItem, guide_items, milestone_titles_for_tag},
+    };
+
+    fn item(number: u64, labels: &[&str]) -> MigrationItem {
+        MigrationItem {
+            number,
+            title: format!("PR {}", number),
+            body: None,
+            html_url: format!("https://github.com/bevyengine/bevy/pull/{}", number),
+            author: "alice".to_string(),
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_area() {
+        let items = vec![
+            item(1, &["A-Rendering", "C-Bug", "M-Needs-Migration-Guide"]),
+            item(2, &["C-Usability", "M-Needs-Migration-Guide"]),
+            item(3, &["A-ECS", "A-Rendering", "M-Needs-Migration-Guide"]),
+            item(4, &["A-Rendering", "M-Needs-Migration-Guide"]),
+        ];
+
+        let groups = group_by_area(&items)
+            .into_iter()
+            .map(|(area, group)| (area, group.iter().map(|item| item.number).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(groups, vec![("A-ECS", vec![3]), ("A-Rendering", vec![1, 4]), ("其他", vec![2])]);
+    }
+
+    #[test]
+    fn test_guide_items() {
+        let items = vec![item(1, &["A-Rendering"]), item(2, &["A-ECS"]), item(3, &["A-Rendering"])];
+
+        // 每个 PR 一条，按领域排列，每条都带上领域标题
+        let guide_items = guide_items(&items);
+        assert_eq!(guide_items.len(), 3);
+        assert!(guide_items[0].starts_with("## A-ECS\nPR #2 "));
+        assert!(guide_items[1].starts_with("## A-Rendering\nPR #1 "));
+        assert!(guide_items[2].starts_with("## A-Rendering\nPR #3 "));
+    }
+
+    #[test]
+    fn test_milestone_titles_for_tag() {
+        assert_eq!(milestone_titles_for_tag("v0.18.0"), vec!["0.18.0", "0.18"]);
+        assert_eq!(milestone_titles_for_tag("v0.17.3"), vec!["0.17.3"]);
+        assert_eq!(milestone_titles_for_tag("0.16.0"), vec!["0.16.0", "0.16"]);
+    }
+}
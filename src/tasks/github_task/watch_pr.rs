@@ -1,118 +1,160 @@
-use actix_rt::spawn;
-use deepseek_api::response::ModelType::DeepSeekReasoner;
 use anyhow::Result;
-use chrono::{Days, Local};
-use deepseek_api::{CompletionsRequestBuilder, RequestBuilder, request::MessageRequest};
+use chrono::NaiveDate;
 use log::{error, info};
 use octocrab::{Octocrab, models::pulls::PullRequest};
-use tokio_schedule::{Job, every};
 
-use crate::{bots::{deepseek_client::build_deepseek_client, github_client::build_github_client, qqbot_client::QQBotClient}, tasks::github_task::{BEVY_OWNER, BEVY_REPO, get_first_deepseek_response}};
-
-
-
-pub fn get_new_prs() -> Result<()> {
-    info!("开始定时抓取prs任务");
+use crate::{AppState, bots::{github_client::build_github_client, llm_client::build_llm_provider, publisher::{Summary, SummaryKind, build_repo_publishers}}, tasks::{github_task::{PageLimit, collect_pages, label_group::{DigestItem, TranslateGroup, group_by_area, translate_groups}, repo::GithubRepo}, glossary::load_glossary, prompt_template::{PromptVars, load_template}, subscription::{SubscriptionItem, route_subscriptions}, summary_record::{Provenance, publish_and_record}, task_run::{TaskName, TaskStats}, time_window::TimeWindow}};
+
+fn format_pr(pr: &PullRequest) -> String {
+    format!(
+        "PR #{} 标题: {:?}, 内容: {:?}，发布者名称：{:?}, 时间UTC: {:?}, 状态: {:?}, 标签: {}, 原文链接: {:?}",
+        pr.number,
+        pr.title,
+        pr.body,
+        pr.user.as_ref().map(|user| &user.login),
+        pr.created_at,
+        pr.state,
+        pr.labels
+            .iter()
+            .flatten()
+            .map(|label| label.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        pr.html_url
+    )
+}
 
-    // let mut now = Local::now().to_utc();
+/// 按代码确定的分组拼接总结，`layout` 和 `texts` 一一对应
+fn render_digest(project: &str, date: NaiveDate, layout: &[(String, usize)], texts: &[String]) -> String {
+    let total = layout.iter().map(|(_, count)| count).sum::<usize>();
 
-    let every_day_task = every(1).day().at(12, 00, 00)
-        .perform(|| async {
-            if let Err(err) = run_pr_task().await {
-                error!("{err:?}");
-            }
-        });
+    let mut text = format!(
+        "每日{} PR总结\n总结日期: {}\n统计: 共{}个PR，涉及{}个领域\n",
+        project,
+        date.format("%Y年%m月%d日"),
+        total,
+        layout.len()
+    );
 
-    spawn(every_day_task);
+    for ((area, count), group_text) in layout.iter().zip(texts) {
+        text.push_str(&format!("\n## {}（{}个）\n{}\n", area, count, group_text.trim()));
+    }
 
-    Ok(())
+    text
 }
 
-pub async fn run_pr_task() -> Result<()> {
+pub async fn run_pr_task(app_state: AppState, repo: GithubRepo, window: TimeWindow) -> Result<TaskStats> {
     let spider = build_github_client()?;
 
-    let pr_list = get_latest_pr_list(&spider).await?;
-
-    let deepseek_client = build_deepseek_client()?;
-
-    let mut all_issue = pr_list.iter().map(|pr| {
-        MessageRequest::user(
-            &format!("PR 内容: {:?}，发布者名称：{:?}, PR 标题：{:?}, 原文链接: {:?}",
-            pr.body, pr.user, pr.title, pr.html_url)
-        )
-    }).collect::<Vec<_>>();
-
-
-    all_issue.push(
-        MessageRequest::user(
-            "你是一个Bevy游戏引擎的社区宣传工作者，你需要根据用户提供的每日的PRs列表信息进行分类总结，总结中需要包含PRs的标题，内容，发布者名称，时间UTC，状态，原文链接，并进行翻译，对其中的游戏引擎底层原理、图形学等专业知识（术语）进行恰当的解释，挑选比较难的，常用的、都比较了解Commit略过。
-            示例PR：
-            假设一个PR：
-
-            标题: Fix memory leak in ECS system
-            内容: There is a memory leak when entities are despawned in the ECS. This causes the game to crash after prolonged play.
-            发布者: john_doe
-            时间: 2023-10-05T12:00:00Z
-            链接: https://github.com/bevyengine/bevy/commit/2facb2572d84e9b9923edef7f35bae2c26308081
-
-            总结：
-            标题: 修复ECS系统中的内存泄漏（翻译）
-            内容: 当实体在ECS中被销毁时，存在内存泄漏问题，导致游戏在长时间运行后崩溃。（翻译和详细解释）
-            发布者: john_doe
-            时间: 2023-10-05 12:00:00 UTC
-            状态: 开启
-            链接:  [GitHub PR #xxxx](原文链接)
-            术语解释: ECS（Entity-Component-System）是一种游戏开发架构，用于管理游戏对象（实体）及其属性（组件）和行为（系统）。内存泄漏是指程序在分配内存后未能释放，导致内存使用不断增加。
-            对于多个PR，可以列出列表。"
-        )
-    );
-
-    let res = CompletionsRequestBuilder::new(&all_issue)
-        .use_model(DeepSeekReasoner)
-        .stream(false)
-        .do_request(&deepseek_client)
-        .await?;
+    let pr_list = get_latest_pr_list(&spider, &repo, &window).await?;
 
-    let text = get_first_deepseek_response(res)?;
+    // 没有数据时也算成功，游标照常推进
+    if pr_list.is_empty() {
+        info!("时间范围内没有PR: {:?}", window);
+        return Ok(TaskStats::default());
+    }
 
-    if !text.is_empty() {
-        // 发送到频道
-        let qq_client = QQBotClient::new_with_default(false).await?;
-        qq_client.send_pr_summary("PRs", &text).await?;
+    let links = pr_list
+        .iter()
+        .filter_map(|pr| pr.html_url.as_ref().map(|url| url.to_string()))
+        .collect::<Vec<_>>();
+    let source_ids = pr_list
+        .iter()
+        .map(|pr| pr.number.to_string())
+        .collect::<Vec<_>>();
+
+    // 分组由代码根据标签确定，AI 只负责每组的翻译和解释
+    let items = pr_list
+        .iter()
+        .map(|pr| DigestItem {
+            labels: pr.labels
+                .iter()
+                .flatten()
+                .map(|label| label.name.clone())
+                .collect(),
+            text: format_pr(pr),
+        })
+        .collect::<Vec<_>>();
+
+    let area_groups = group_by_area(&items);
+    let layout = area_groups
+        .iter()
+        .map(|(area, group)| (area.to_string(), group.len()))
+        .collect::<Vec<_>>();
+    let groups = area_groups
+        .into_iter()
+        .map(|(area, group)| TranslateGroup {
+            context: area.to_string(),
+            lines: group.iter().map(|item| item.text.clone()).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let llm = build_llm_provider(TaskName::Prs.as_str())?;
+    let template = load_template(&app_state, "prs").await?;
+    let glossary = load_glossary(&app_state).await;
+
+    let translation = translate_groups(
+        llm.as_ref(),
+        &template.render(&PromptVars::new(window.date(), &repo.full_name(), pr_list.len())),
+        &glossary,
+        &groups,
+    )
+    .await?;
+
+    let text = render_digest(repo.project_name(), window.date(), &layout, &translation.texts);
+
+    // 发送到频道
+    let publishers = build_repo_publishers(&app_state, &repo).await?;
+    let summary = Summary::daily(SummaryKind::Prs, &repo.daily_name("PRs"), window.date(), &text)
+        .with_links(links);
+    let provenance = Provenance {
+        source_ids,
+        prompt_version: template.version_tag(),
+        model: translation.model.clone(),
+        raw_output: translation.texts.join("\n\n"),
+    };
+    publish_and_record(&app_state, &publishers, &summary, &provenance).await?;
+
+    // 订阅发送失败不影响每日总结
+    let subscription_items = pr_list.iter().map(SubscriptionItem::from_pr).collect::<Vec<_>>();
+    if let Err(err) = route_subscriptions(&app_state, &repo, SummaryKind::Prs, window.date(), &subscription_items).await {
+        error!("发送订阅发生错误：{err:?}");
     }
 
-    Ok(())
+    Ok(TaskStats::new(pr_list.len(), translation.llm_latency).with_model(&translation.model))
 }
 
 
 pub async fn get_latest_pr_list(
-    spider: &Octocrab
+    spider: &Octocrab,
+    repo: &GithubRepo,
+    window: &TimeWindow
 ) -> Result<Vec<PullRequest>> {
 
-    let pr_list = spider.pulls(BEVY_OWNER, BEVY_REPO)
+    let limit = PageLimit::from_env();
+
+    // 按创建时间倒序，读到时间范围之前的 PR 后不再读取下一页
+    let first_page = spider.pulls(&repo.owner, &repo.name)
         .list()
         .state(octocrab::params::State::All)
+        .sort(octocrab::params::pulls::Sort::Created)
+        .direction(octocrab::params::Direction::Descending)
+        .per_page(limit.per_page)
         .send()
         .await?;
 
-    let last_day = Local::now().checked_sub_days(Days::new(1)).unwrap().to_utc();
-
-    // 筛选今日的
-    let latest_pr_list = pr_list.items.iter().filter(|pr| {
-        if let Some(created_at) = pr.created_at {
-            if created_at > last_day {
-                return true;
-            }
-        }
-
-        false
+    let pr_list = collect_pages(spider, first_page, &limit, |prs| {
+        prs.last()
+            .and_then(|pr| pr.created_at)
+            .is_some_and(|created_at| created_at < window.since)
     })
-    .cloned()
-    .collect::<Vec<_>>();
+    .await?;
 
-    if latest_pr_list.is_empty() {
-        anyhow::bail!("今日Pr列表为空");
-    }
+    // 筛选时间范围内的
+    let latest_pr_list = pr_list
+        .into_iter()
+        .filter(|pr| pr.created_at.is_some_and(|created_at| window.contains(created_at)))
+        .collect::<Vec<_>>();
 
     Ok(latest_pr_list)
 }
@@ -121,9 +163,27 @@ pub async fn get_latest_pr_list(
 
 #[cfg(test)]
 mod tests {
+    use chrono::NaiveDate;
     use dotenvy::dotenv;
 
-    use crate::tasks::github_task::watch_pr::get_latest_pr_list;
+    use crate::tasks::{github_task::{repo::{DEFAULT_REPO, GithubRepo}, watch_pr::{get_latest_pr_list, render_digest}}, time_window::TimeWindow};
+
+    #[test]
+    fn test_render_digest() {
+        let layout = vec![("A-ECS".to_string(), 2), ("其他".to_string(), 1)];
+        let texts = vec!["ecs".to_string(), "other\n".to_string()];
+        let date = NaiveDate::from_ymd_opt(2025, 11, 10).unwrap();
+
+        assert_eq!(
+            render_digest("Bevy", date, &layout, &texts),
+            "每日Bevy PR总结\n总结日期: 2025年11月10日\n统计: 共3个PR，涉及2个领域\n\
+            \n## A-ECS（2个）\necs\n\n## 其他（1个）\nother\n"
+        );
+
+        // 其他仓库使用仓库名称
+        let project = GithubRepo::parse("bevyengine/bevy-website").unwrap();
+        assert!(render_digest(project.project_name(), date, &layout, &texts).starts_with("每日bevy-website PR总结\n"));
+    }
 
     #[tokio::test]
     async fn test_get_latest_pr_list() {
@@ -135,7 +195,8 @@ mod tests {
             .build()
             .unwrap();
 
-        let pr_list = get_latest_pr_list(&spider).await.unwrap();
+        let repo = GithubRepo::parse(DEFAULT_REPO).unwrap();
+        let pr_list = get_latest_pr_list(&spider, &repo, &TimeWindow::last_day()).await.unwrap();
 
         println!("{:?}", pr_list);
     }
@@ -0,0 +1,286 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use octocrab::models::repos::Release;
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+use crate::{
+    AppState,
+    bots::{
+        github_client::build_github_client, llm_client::{ChatMessage, LlmProvider, build_llm_provider},
+        publisher::{Publishers, Summary, SummaryKind, build_repo_publishers},
+    },
+    tasks::{
+        github_task::repo::GithubRepo,
+        glossary::load_glossary,
+        job_cursor::{get_cursor, save_cursor},
+        prompt_template::{PromptVars, load_template},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, TaskStats},
+    },
+    util::cache,
+};
+
+// 每次只检查最近的几个版本，更早的版本视为已经发布过
+const RECENT_RELEASES: u8 = 10;
+// 发布中的版本占用时间，覆盖 AI 翻译和发布的耗时
+const RELEASE_CLAIM_TTL_SEC: u64 = 3600;
+
+/// 需要发布的版本信息，定时任务和 Webhook 共用
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseNote {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl ReleaseNote {
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.tag_name)
+    }
+
+    /// `project` 为项目名称，例如 `Bevy`
+    pub fn title(&self, project: &str) -> String {
+        if self.prerelease {
+            format!("{} {} 预发布", project, self.display_name())
+        } else {
+            format!("{} {} 发布", project, self.display_name())
+        }
+    }
+}
+
+impl From<Release> for ReleaseNote {
+    fn from(release: Release) -> Self {
+        Self {
+            tag_name: release.tag_name,
+            name: release.name,
+            body: release.body,
+            html_url: release.html_url.to_string(),
+            draft: release.draft,
+            prerelease: release.prerelease,
+            published_at: release.published_at,
+        }
+    }
+}
+
+/// 检查最近的版本，发布还没有发布过的
+///
+/// Bevy 的每个版本标签都会创建 Release，这里只读取 Release 列表；
+/// 仓库第一次运行时只记录已有的版本，不发布
+pub async fn run_release_task(app_state: AppState, repo: GithubRepo) -> Result<TaskStats> {
+    let spider = build_github_client()?;
+
+    let release_list = spider
+        .repos(&repo.owner, &repo.name)
+        .releases()
+        .list()
+        .per_page(RECENT_RELEASES)
+        .send()
+        .await?;
+
+    // 不能按 release_post 是否为空判断，Webhook 可能先记录了一个版本
+    let seed_marker = release_seed_marker(&repo);
+    if get_cursor(&app_state, &seed_marker).await?.is_none() {
+        for release in release_list.items.into_iter().map(ReleaseNote::from) {
+            if !release.draft && !is_published(&app_state, &repo, &release.tag_name).await? {
+                save_release(&app_state, &repo, &release).await?;
+            }
+        }
+        save_cursor(&app_state, &seed_marker, Utc::now()).await?;
+        info!("首次检查 {} 的版本，已记录现有版本，不发布", repo.full_name());
+        return Ok(TaskStats::default());
+    }
+
+    // 从旧到新发布
+    let mut new_releases = vec![];
+    for release in release_list.items.into_iter().rev().map(ReleaseNote::from) {
+        if release.draft {
+            continue;
+        }
+
+        if is_published(&app_state, &repo, &release.tag_name).await? {
+            debug!("已经发布过版本: {}，跳过处理", release.tag_name);
+        } else {
+            new_releases.push(release);
+        }
+    }
+
+    if new_releases.is_empty() {
+        return Ok(TaskStats::default());
+    }
+
+    let llm = build_llm_provider(TaskName::Releases.as_str())?;
+    let publishers = build_repo_publishers(&app_state, &repo).await?;
+
+    let mut stats = TaskStats::default();
+    for release in new_releases {
+        match process_release(&app_state, llm.as_ref(), &publishers, &repo, &release).await {
+            Ok(release_stats) => stats += release_stats,
+            Err(err) => stats.add_failure(&format!("版本 {}", release.tag_name), &err),
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 发布单个版本，已经发布过时跳过，Webhook 收到新版本时调用
+pub async fn publish_release(app_state: &AppState, repo: &GithubRepo, release: &ReleaseNote) -> Result<TaskStats> {
+    if is_published(app_state, repo, &release.tag_name).await? {
+        info!("已经发布过版本: {}，跳过处理", release.tag_name);
+        return Ok(TaskStats::default());
+    }
+
+    let llm = build_llm_provider(TaskName::Releases.as_str())?;
+    let publishers = build_repo_publishers(app_state, repo).await?;
+
+    process_release(app_state, llm.as_ref(), &publishers, repo, release).await
+}
+
+/// 记录仓库已经写入过现有版本，保存在 `job_cursor` 表中
+fn release_seed_marker(repo: &GithubRepo) -> String {
+    format!("release-seed@{}", repo.full_name())
+}
+
+async fn is_published(app_state: &AppState, repo: &GithubRepo, tag_name: &str) -> Result<bool> {
+    let exist = entity::release_post::Entity::find()
+        .filter(entity::release_post::Column::Repo.eq(repo.full_name()))
+        .filter(entity::release_post::Column::TagName.eq(tag_name))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(exist.is_some())
+}
+
+/// 定时任务和 Webhook 可能同时处理同一个版本，先占用版本再请求 AI，避免重复发布
+///
+/// 失败时释放占用，下次检查时重试
+async fn process_release(
+    app_state: &AppState,
+    llm: &dyn LlmProvider,
+    publishers: &Publishers,
+    repo: &GithubRepo,
+    release: &ReleaseNote,
+) -> Result<TaskStats> {
+    let claim_key = format!("release_post_{}_{}", repo.full_name(), release.tag_name);
+    if !cache::put_nx_ttl(&app_state.redis, &claim_key, "1", RELEASE_CLAIM_TTL_SEC).await? {
+        info!("版本 {} 正在发布，跳过处理", release.tag_name);
+        return Ok(TaskStats::default());
+    }
+
+    // 占用前另一方可能刚好发布完成
+    if is_published(app_state, repo, &release.tag_name).await? {
+        return Ok(TaskStats::default());
+    }
+
+    let res = translate_release(app_state, llm, publishers, repo, release).await;
+    if res.is_err() {
+        cache::del(&app_state.redis, &claim_key).await?;
+    }
+
+    res
+}
+
+async fn translate_release(
+    app_state: &AppState,
+    llm: &dyn LlmProvider,
+    publishers: &Publishers,
+    repo: &GithubRepo,
+    release: &ReleaseNote,
+) -> Result<TaskStats> {
+    let release_main_message = format!(
+        "版本: {}, 标签: {}, 预发布: {}, 发布时间UTC: {:?}, 原文链接: {}, 发布说明: {}",
+        release.display_name(),
+        release.tag_name,
+        release.prerelease,
+        release.published_at,
+        release.html_url,
+        release.body.as_deref().unwrap_or("")
+    );
+
+    let template = load_template(app_state, "release").await?;
+    let terms = load_glossary(app_state).await.relevant(&release_main_message);
+    let chat_messages = vec![
+        ChatMessage::system(&terms.inject(&template.render(&PromptVars::today(&repo.full_name(), 1)))),
+        ChatMessage::user(&release_main_message),
+    ];
+
+    let now = Instant::now();
+    info!("开始请求AI翻译版本发布说明: {}, 后端: {}", release.tag_name, llm.name());
+
+    let output = llm.chat(&chat_messages, Some(8192)).await?;
+
+    let llm_latency = now.elapsed();
+    info!("AI翻译完成, 耗时: {}秒, Token: {}", llm_latency.as_secs_f32(), output.usage.total_tokens);
+    terms.check(&release.tag_name, &output.text);
+
+    // 发布到公告频道
+    let summary = Summary::new(SummaryKind::Release, &release.title(repo.project_name()), &output.text)
+        .with_links(vec![release.html_url.clone()]);
+    let provenance = Provenance {
+        source_ids: vec![release.tag_name.clone()],
+        prompt_version: template.version_tag(),
+        model: output.model.clone(),
+        raw_output: output.text,
+    };
+    publish_and_record(app_state, publishers, &summary, &provenance).await?;
+
+    save_release(app_state, repo, release).await?;
+
+    Ok(TaskStats::new(1, llm_latency).with_model(&output.model))
+}
+
+async fn save_release(app_state: &AppState, repo: &GithubRepo, release: &ReleaseNote) -> Result<()> {
+    let new_release = entity::release_post::ActiveModel {
+        id: NotSet,
+        tag_name: Set(release.tag_name.clone()),
+        name: Set(release.display_name().to_string()),
+        html_url: Set(release.html_url.clone()),
+        published_at: Set(release.published_at.map(|published_at| published_at.naive_utc())),
+        created_at: Set(Utc::now().naive_utc()),
+        repo: Set(repo.full_name()),
+    };
+    new_release.insert(&app_state.mysql).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bots::github_client::build_github_client, tasks::github_task::{repo::{DEFAULT_REPO, GithubRepo}, watch_releases::ReleaseNote}};
+
+    #[test]
+    fn test_release_title() {
+        let release: ReleaseNote = serde_json::from_str(
+            r#"{"tag_name": "v0.18.0-rc.1", "name": "", "body": null, "html_url": "https://github.com/bevyengine/bevy/releases/tag/v0.18.0-rc.1", "prerelease": true, "published_at": "2025-12-01T10:00:00Z"}"#
+        ).unwrap();
+        assert_eq!(release.title("Bevy"), "Bevy v0.18.0-rc.1 预发布");
+
+        let release = ReleaseNote { name: Some("v0.17.3".to_string()), prerelease: false, ..release };
+        assert_eq!(release.title("Bevy"), "Bevy v0.17.3 发布");
+        assert_eq!(release.title("bevy_editor_prototypes"), "bevy_editor_prototypes v0.17.3 发布");
+    }
+
+    #[tokio::test]
+    async fn test_list_releases() {
+        dotenvy::dotenv().ok();
+
+        let spider = build_github_client().unwrap();
+        let repo = GithubRepo::parse(DEFAULT_REPO).unwrap();
+        let list = spider.repos(&repo.owner, &repo.name).releases().list().per_page(5).send().await.unwrap();
+        for release in list {
+            println!("版本: {}, 发布时间: {:?}", release.tag_name, release.published_at);
+        }
+    }
+}
@@ -0,0 +1,409 @@
+use std::{env, time::Instant};
+
+use anyhow::Result;
+use log::{error, info};
+use serde::Deserialize;
+
+use crate::{
+    AppState,
+    bots::{
+        llm_client::{ChatMessage, build_llm_provider},
+        publisher::{Summary, SummaryKind, build_repo_publishers},
+    },
+    tasks::{
+        github_task::{
+            repo::{DEFAULT_REPO, GithubRepo, load_repo},
+            watch_migration_guide::generate_for_release,
+            watch_releases::{ReleaseNote, publish_release},
+        },
+        glossary::load_glossary,
+        prompt_template::{PromptVars, load_template},
+        summary_record::{Provenance, publish_and_record},
+        task_run::{TaskName, Trigger, execute_run},
+        time_window::TimeWindow,
+    },
+    util::cache,
+};
+
+// 紧急通知使用的 AI 后端配置名称，对应 `LLM_PROVIDER_WEBHOOK`
+const LLM_TASK: &str = "webhook";
+// 同一个 Issue/PR 30天内只推送一次
+const URGENT_DEDUP_TTL_SEC: u64 = 30 * 24 * 3600;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookLabel {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookUser {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WebhookRepository {
+    full_name: String,
+}
+
+/// Issue 和 PR 共用的字段
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookItem {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub user: WebhookUser,
+    #[serde(default)]
+    pub labels: Vec<WebhookLabel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesPayload {
+    action: String,
+    issue: WebhookItem,
+    /// `labeled` 事件中新加的标签
+    label: Option<WebhookLabel>,
+    repository: Option<WebhookRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestPayload {
+    action: String,
+    pull_request: WebhookItem,
+    label: Option<WebhookLabel>,
+    repository: Option<WebhookRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleasePayload {
+    action: String,
+    release: ReleaseNote,
+    repository: Option<WebhookRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MilestonePayload {
+    action: String,
+    repository: Option<WebhookRepository>,
+}
+
+/// Webhook 事件需要执行的处理
+#[derive(Debug, Clone)]
+pub enum WebhookAction {
+    /// 带紧急标签的 Issue/PR，立即推送
+    Urgent(SummaryKind, WebhookItem),
+    /// 新版本发布
+    Release(ReleaseNote),
+    /// 运行对应的定时任务，由任务自己去重
+    RunTask(TaskName),
+}
+
+/// 需要处理的事件和所属的仓库
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    /// `owner/repo`
+    pub repo: String,
+    pub action: WebhookAction,
+}
+
+/// 需要立即推送的标签，环境变量 `WEBHOOK_URGENT_LABELS`，逗号分隔
+pub fn urgent_labels() -> Vec<String> {
+    env::var("WEBHOOK_URGENT_LABELS")
+        .unwrap_or("P-Crash,P-Critical,P-Unsound".to_string())
+        .split(',')
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect()
+}
+
+/// 根据事件类型（`X-GitHub-Event`）和内容决定如何处理，不是监听的仓库或者不需要处理时返回 None
+pub fn route_event(
+    event: &str,
+    body: &[u8],
+    urgent_labels: &[String],
+    watched_repos: &[String],
+) -> Result<Option<WebhookEvent>> {
+    let (repo, action) = match event {
+        "issues" => {
+            let payload: IssuesPayload = serde_json::from_slice(body)?;
+            let Some(repo) = watched_repo(&payload.repository, watched_repos) else {
+                return Ok(None);
+            };
+
+            // 加入里程碑的 Issue 交给里程碑任务处理
+            let action = if payload.action == "milestoned" {
+                Some(WebhookAction::RunTask(TaskName::Milestones))
+            } else {
+                route_item(&payload.action, payload.issue, payload.label, urgent_labels)
+                    .map(|item| WebhookAction::Urgent(SummaryKind::Issues, item))
+            };
+            (repo, action)
+        }
+        "pull_request" => {
+            let payload: PullRequestPayload = serde_json::from_slice(body)?;
+            let Some(repo) = watched_repo(&payload.repository, watched_repos) else {
+                return Ok(None);
+            };
+
+            let action = route_item(&payload.action, payload.pull_request, payload.label, urgent_labels)
+                .map(|item| WebhookAction::Urgent(SummaryKind::Prs, item));
+            (repo, action)
+        }
+        "release" => {
+            let payload: ReleasePayload = serde_json::from_slice(body)?;
+            let Some(repo) = watched_repo(&payload.repository, watched_repos) else {
+                return Ok(None);
+            };
+            if payload.release.draft {
+                return Ok(None);
+            }
+
+            let action = (payload.action == "published").then_some(WebhookAction::Release(payload.release));
+            (repo, action)
+        }
+        "milestone" => {
+            let payload: MilestonePayload = serde_json::from_slice(body)?;
+            let Some(repo) = watched_repo(&payload.repository, watched_repos) else {
+                return Ok(None);
+            };
+
+            // 默认仓库的里程碑关闭时生成迁移指南
+            let action = match payload.action.as_str() {
+                "created" | "edited" | "opened" => Some(WebhookAction::RunTask(TaskName::Milestones)),
+                "closed" if repo == DEFAULT_REPO => Some(WebhookAction::RunTask(TaskName::MigrationGuide)),
+                _ => None,
+            };
+            (repo, action)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(action.map(|action| WebhookEvent { repo, action }))
+}
+
+fn watched_repo(repository: &Option<WebhookRepository>, watched_repos: &[String]) -> Option<String> {
+    repository
+        .as_ref()
+        .map(|repository| repository.full_name.clone())
+        .filter(|full_name| watched_repos.contains(full_name))
+}
+
+/// 新开或重新打开时带有紧急标签，或者新加了紧急标签
+fn route_item(
+    action: &str,
+    item: WebhookItem,
+    label: Option<WebhookLabel>,
+    urgent_labels: &[String],
+) -> Option<WebhookItem> {
+    let is_urgent = match action {
+        "opened" | "reopened" => item.labels.iter().any(|label| urgent_labels.contains(&label.name)),
+        "labeled" => label.is_some_and(|label| urgent_labels.contains(&label.name)),
+        _ => false,
+    };
+
+    is_urgent.then_some(item)
+}
+
+/// 处理一次 Webhook 事件
+pub async fn handle_action(app_state: AppState, event: WebhookEvent) -> Result<()> {
+    let repo = load_repo(&app_state, Some(&event.repo)).await?;
+
+    match event.action {
+        WebhookAction::Urgent(kind, item) => {
+            let key = format!("webhook_urgent_{}_{}_{}", repo.full_name(), kind.as_str(), item.number);
+            if !cache::put_nx_ttl(&app_state.redis, &key, "1", URGENT_DEDUP_TTL_SEC).await? {
+                info!("已推送过: {} #{}，跳过", kind.as_str(), item.number);
+                return Ok(());
+            }
+
+            let kind_name = match kind {
+                SummaryKind::Prs => "PR",
+                _ => "Issue",
+            };
+            let title = format!("紧急 {} #{}：{}", repo.daily_name(kind_name), item.number, item.title);
+            let message = format!(
+                "标题: {}, 内容: {:?}，发布者名称：{}, 标签: {}, 原文链接: {}",
+                item.title,
+                item.body,
+                item.user.login,
+                item.labels.iter().map(|label| label.name.as_str()).collect::<Vec<_>>().join(", "),
+                item.html_url
+            );
+
+            let res = publish_translation(&app_state, &repo, kind, &title, &message, &item.html_url, &item.number.to_string()).await;
+            if res.is_err() {
+                // 失败时允许 GitHub 重新投递后再次推送
+                cache::del(&app_state.redis, &key).await?;
+            }
+            res
+        }
+        WebhookAction::Release(release) => {
+            publish_release(&app_state, &repo, &release).await?;
+            if !release.prerelease && repo.is_default() {
+                generate_for_release(&app_state, &repo, &release.tag_name).await?;
+            }
+            Ok(())
+        }
+        WebhookAction::RunTask(name) => {
+            let job_name = repo.job_name(name);
+            let run = execute_run(app_state, &job_name, name, repo, Trigger::Webhook, TimeWindow::last_day()).await?;
+            info!("Webhook 触发任务: {}, 运行ID: {}, 状态: {}", run.job_name, run.id, run.status);
+            Ok(())
+        }
+    }
+}
+
+/// 翻译单条内容并立即发布
+async fn publish_translation(
+    app_state: &AppState,
+    repo: &GithubRepo,
+    kind: SummaryKind,
+    title: &str,
+    message: &str,
+    link: &str,
+    source_id: &str,
+) -> Result<()> {
+    let llm = build_llm_provider(LLM_TASK)?;
+    let template = load_template(app_state, "webhook-urgent").await?;
+    let terms = load_glossary(app_state).await.relevant(message);
+
+    let chat_messages = vec![
+        ChatMessage::system(&terms.inject(&template.render(&PromptVars::today(&repo.full_name(), 1)))),
+        ChatMessage::user(message),
+    ];
+
+    let now = Instant::now();
+    info!("开始请求AI翻译: {}, 后端: {}", title, llm.name());
+
+    let output = llm.chat(&chat_messages, None).await?;
+    info!("AI翻译完成, 耗时: {}秒, Token: {}", now.elapsed().as_secs_f32(), output.usage.total_tokens);
+    terms.check(title, &output.text);
+
+    let publishers = build_repo_publishers(app_state, repo).await?;
+    let summary = Summary::new(kind, title, &output.text)
+        .with_links(vec![link.to_string()]);
+    let provenance = Provenance {
+        source_ids: vec![source_id.to_string()],
+        prompt_version: template.version_tag(),
+        model: output.model,
+        raw_output: output.text,
+    };
+    publish_and_record(app_state, &publishers, &summary, &provenance).await?;
+
+    Ok(())
+}
+
+/// 在后台处理事件，GitHub 要求10秒内响应，不能等待 AI 请求完成
+pub fn spawn_action(app_state: AppState, event: WebhookEvent, delivery_id: String) {
+    actix_rt::spawn(async move {
+        let redis = app_state.redis.clone();
+        if let Err(err) = handle_action(app_state, event).await {
+            error!("处理 Webhook 事件失败: {}, {err:?}", delivery_id);
+            // 删除投递记录，GitHub 重新投递时可以再次处理
+            if let Err(err) = cache::del(&redis, &delivery_key(&delivery_id)).await {
+                error!("{err:?}");
+            }
+        }
+    });
+}
+
+/// 投递ID去重使用的缓存键
+pub fn delivery_key(delivery_id: &str) -> String {
+    format!("github_delivery_{}", delivery_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bots::publisher::SummaryKind,
+        tasks::{
+            github_task::webhook::{WebhookAction, WebhookEvent, route_event},
+            task_run::TaskName,
+        },
+    };
+
+    const ISSUES_LABELED: &str = include_str!("../../../tests/fixtures/webhook/issues_labeled.json");
+    const PULL_REQUEST_OPENED: &str = include_str!("../../../tests/fixtures/webhook/pull_request_opened.json");
+    const RELEASE_PUBLISHED: &str = include_str!("../../../tests/fixtures/webhook/release_published.json");
+    const MILESTONE_CLOSED: &str = include_str!("../../../tests/fixtures/webhook/milestone_closed.json");
+
+    fn labels() -> Vec<String> {
+        vec!["P-Crash".to_string(), "P-Critical".to_string()]
+    }
+
+    fn route(event: &str, body: &str, labels: &[String]) -> Option<WebhookAction> {
+        let repos = vec!["bevyengine/bevy".to_string(), "bevyengine/bevy-website".to_string()];
+        route_event(event, body.as_bytes(), labels, &repos)
+            .unwrap()
+            .map(|event| event.action)
+    }
+
+    #[test]
+    fn test_route_issues() {
+        let action = route("issues", ISSUES_LABELED, &labels());
+        let Some(WebhookAction::Urgent(SummaryKind::Issues, item)) = action else {
+            panic!("应该立即推送: {:?}", action);
+        };
+        assert_eq!(item.number, 21380);
+        assert_eq!(item.user.login, "alice");
+
+        // 新加的标签不是紧急标签
+        let action = route("issues", ISSUES_LABELED, &["P-High".to_string()]);
+        assert!(action.is_none());
+
+        let body = ISSUES_LABELED.replace("\"labeled\"", "\"milestoned\"");
+        let action = route("issues", &body, &labels());
+        assert!(matches!(action, Some(WebhookAction::RunTask(TaskName::Milestones))));
+
+        // 其他仓库的事件
+        let body = ISSUES_LABELED.replace("bevyengine/bevy", "someone/bevy");
+        assert!(route("issues", &body, &labels()).is_none());
+    }
+
+    #[test]
+    fn test_route_pull_request() {
+        let action = route("pull_request", PULL_REQUEST_OPENED, &labels());
+        assert!(matches!(action, Some(WebhookAction::Urgent(SummaryKind::Prs, ref item)) if item.number == 21391));
+
+        let body = PULL_REQUEST_OPENED.replace("\"opened\"", "\"synchronize\"");
+        assert!(route("pull_request", &body, &labels()).is_none());
+    }
+
+    #[test]
+    fn test_route_release_and_milestone() {
+        let action = route("release", RELEASE_PUBLISHED, &labels());
+        assert!(matches!(action, Some(WebhookAction::Release(ref release)) if release.tag_name == "v0.17.3"));
+
+        let body = RELEASE_PUBLISHED.replace("\"draft\": false", "\"draft\": true");
+        assert!(route("release", &body, &labels()).is_none());
+
+        let action = route("milestone", MILESTONE_CLOSED, &labels());
+        assert!(matches!(action, Some(WebhookAction::RunTask(TaskName::MigrationGuide))));
+
+        let body = MILESTONE_CLOSED.replace("\"action\": \"closed\"", "\"action\": \"created\"");
+        let action = route("milestone", &body, &labels());
+        assert!(matches!(action, Some(WebhookAction::RunTask(TaskName::Milestones))));
+
+        assert!(route("star", "{}", &labels()).is_none());
+        assert!(route_event("issues", b"{}", &labels(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_route_other_repo() {
+        let repos = vec!["bevyengine/bevy-website".to_string()];
+
+        let body = ISSUES_LABELED.replace("bevyengine/bevy", "bevyengine/bevy-website");
+        let event = route_event("issues", body.as_bytes(), &labels(), &repos).unwrap();
+        assert!(matches!(
+            event,
+            Some(WebhookEvent { ref repo, action: WebhookAction::Urgent(SummaryKind::Issues, _) }) if repo == "bevyengine/bevy-website"
+        ));
+
+        // 默认仓库没有在列表中
+        assert!(route_event("issues", ISSUES_LABELED.as_bytes(), &labels(), &repos).unwrap().is_none());
+
+        // 只有默认仓库生成迁移指南
+        let body = MILESTONE_CLOSED.replace("bevyengine/bevy", "bevyengine/bevy-website");
+        assert!(route_event("milestone", body.as_bytes(), &labels(), &repos).unwrap().is_none());
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::Result;
+use log::warn;
+use sea_orm::{EntityTrait, QueryOrder};
+
+use crate::AppState;
+
+/// 术语表中的一个术语
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryTerm {
+    /// 英文术语，例如 `render graph`
+    pub term: String,
+    /// 推荐的中文译法，例如 `渲染图`
+    pub translation: String,
+    /// 简短说明，帮助 AI 判断上下文
+    pub explanation: Option<String>,
+    /// 已知的错误译法，例如 `system` 被译为 `系统函数`，输出中出现时视为偏离
+    pub variants: Vec<String>,
+}
+
+impl GlossaryTerm {
+    pub fn from_model(model: entity::glossary::Model) -> Self {
+        Self {
+            term: model.term,
+            translation: model.translation,
+            explanation: model.explanation.filter(|explanation| !explanation.is_empty()),
+            variants: model
+                .variants
+                .and_then(|variants| serde_json::from_str(&variants).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 统一术语译法：只把输入中出现的术语加入提示词，并检查输出是否使用了推荐译法
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Glossary {
+    terms: Vec<GlossaryTerm>,
+}
+
+impl Glossary {
+    pub fn new(terms: Vec<GlossaryTerm>) -> Self {
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// 输入中出现的术语，不区分大小写，按完整单词匹配，允许复数形式
+    pub fn relevant(&self, input: &str) -> Self {
+        let input = input.to_lowercase();
+        let terms = self.terms
+            .iter()
+            .filter(|term| contains_term(&input, &term.term.to_lowercase()))
+            .cloned()
+            .collect();
+
+        Self { terms }
+    }
+
+    /// 文本中已经使用推荐译法或保留英文原文的术语，用于检查合并部分总结后的输出
+    pub fn mentioned(&self, text: &str) -> Self {
+        let lowercase = text.to_lowercase();
+        let terms = self.terms
+            .iter()
+            .filter(|term| text.contains(&term.translation) || contains_term(&lowercase, &term.term.to_lowercase()))
+            .cloned()
+            .collect();
+
+        Self { terms }
+    }
+
+    /// 在提示词末尾追加术语表，没有相关术语时保持原样
+    pub fn inject(&self, prompt: &str) -> String {
+        if self.is_empty() {
+            return prompt.to_string();
+        }
+
+        let mut prompt = format!("{}\n\n术语表（以下术语必须使用指定译法，代码标识符保持英文原样）：", prompt);
+        for term in &self.terms {
+            prompt.push_str(&format!("\n- {} => {}", term.term, term.translation));
+            if let Some(explanation) = &term.explanation {
+                prompt.push_str(&format!("（{}）", explanation));
+            }
+            if !term.variants.is_empty() {
+                prompt.push_str(&format!("，不要译为：{}", term.variants.join("、")));
+            }
+        }
+
+        prompt
+    }
+
+    /// 输出中出现了已知的错误译法，或者既没有推荐译法也没有保留英文原文的术语，可能被翻译成了其他说法
+    ///
+    /// 错误译法可能包含推荐译法（`系统函数` 包含 `系统`），所以先检查错误译法
+    pub fn deviations(&self, output: &str) -> Vec<&GlossaryTerm> {
+        let lowercase = output.to_lowercase();
+        self.terms
+            .iter()
+            .filter(|term| {
+                term.variants.iter().any(|variant| output.contains(variant.as_str()))
+                    || (!output.contains(&term.translation) && !contains_term(&lowercase, &term.term.to_lowercase()))
+            })
+            .collect()
+    }
+
+    /// 检查输出并记录偏离术语表的术语，不影响发布
+    pub fn check(&self, task: &str, output: &str) {
+        let deviations = self.deviations(output);
+        if deviations.is_empty() {
+            return;
+        }
+
+        let terms = deviations
+            .iter()
+            .map(|term| format!("{} => {}", term.term, term.translation))
+            .collect::<Vec<_>>()
+            .join(", ");
+        warn!("{} 的 AI 输出可能没有使用术语表译法: {}", task, terms);
+    }
+}
+
+/// 读取完整术语表，读取失败时不使用术语表，避免影响总结任务
+pub async fn load_glossary(app_state: &AppState) -> Glossary {
+    match find_terms(app_state).await {
+        Ok(terms) => Glossary::new(terms),
+        Err(err) => {
+            warn!("读取术语表失败: {err:?}");
+            Glossary::default()
+        }
+    }
+}
+
+async fn find_terms(app_state: &AppState) -> Result<Vec<GlossaryTerm>> {
+    let models = entity::glossary::Entity::find()
+        .order_by_asc(entity::glossary::Column::Term)
+        .all(&app_state.mysql)
+        .await?;
+
+    Ok(models.into_iter().map(GlossaryTerm::from_model).collect())
+}
+
+/// `text` 和 `term` 都已转为小写
+fn contains_term(text: &str, term: &str) -> bool {
+    if term.is_empty() {
+        return false;
+    }
+
+    text.match_indices(term).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let rest = &text[start + term.len()..];
+        let rest = rest.strip_prefix("es").or_else(|| rest.strip_prefix('s')).unwrap_or(rest);
+        let after = rest.chars().next();
+
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::glossary::{Glossary, GlossaryTerm};
+
+    fn glossary() -> Glossary {
+        Glossary::new(vec![
+            GlossaryTerm {
+                term: "render graph".to_string(),
+                translation: "渲染图".to_string(),
+                explanation: Some("渲染节点组成的有向图".to_string()),
+                variants: vec![],
+            },
+            GlossaryTerm {
+                term: "system".to_string(),
+                translation: "系统".to_string(),
+                explanation: None,
+                variants: vec!["系统函数".to_string()],
+            },
+            GlossaryTerm { term: "query".to_string(), translation: "查询".to_string(), explanation: None, variants: vec![] },
+        ])
+    }
+
+    #[test]
+    fn test_relevant() {
+        let glossary = glossary();
+
+        let relevant = glossary.relevant("Fix Render Graph ordering in observer Systems");
+        let terms = relevant.terms.iter().map(|term| term.term.as_str()).collect::<Vec<_>>();
+        assert_eq!(terms, vec!["render graph", "system"]);
+
+        // 单词的一部分不算
+        assert!(glossary.relevant("subsystem queryable").is_empty());
+        assert!(glossary.relevant("add `Query` filter").terms.iter().any(|term| term.term == "query"));
+    }
+
+    #[test]
+    fn test_mentioned() {
+        let glossary = glossary();
+
+        let mentioned = glossary.mentioned("修复渲染图中 Query 的排序");
+        let terms = mentioned.terms.iter().map(|term| term.term.as_str()).collect::<Vec<_>>();
+        assert_eq!(terms, vec!["render graph", "query"]);
+        assert!(glossary.mentioned("修复崩溃").is_empty());
+    }
+
+    #[test]
+    fn test_inject() {
+        let glossary = glossary().relevant("render graph and systems");
+
+        assert_eq!(
+            glossary.inject("请翻译"),
+            "请翻译\n\n术语表（以下术语必须使用指定译法，代码标识符保持英文原样）：\n- render graph => 渲染图（渲染节点组成的有向图）\n- system => 系统，不要译为：系统函数"
+        );
+        assert_eq!(Glossary::default().inject("请翻译"), "请翻译");
+    }
+
+    #[test]
+    fn test_deviations() {
+        let glossary = glossary().relevant("render graph and systems");
+
+        assert!(glossary.deviations("修复渲染图中系统的执行顺序").is_empty());
+        // 保留英文原文不算偏离
+        assert!(glossary.deviations("修复 render graph 中系统的执行顺序").is_empty());
+
+        let deviations = glossary.deviations("修复渲染流程中系统的执行顺序");
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].term, "render graph");
+
+        // 错误译法包含推荐译法，也算偏离
+        let deviations = glossary.deviations("修复渲染图中系统函数的执行顺序");
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].term, "system");
+    }
+}
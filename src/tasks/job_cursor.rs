@@ -0,0 +1,84 @@
+use anyhow::Result;
+use chrono::{DateTime, Days, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+};
+
+use crate::{AppState, tasks::time_window::TimeWindow, util::cache};
+
+// 游标锁的有效时间，进程异常退出时锁会自动过期
+const CURSOR_LOCK_TTL_SEC: u64 = 6 * 3600;
+
+/// 任务上一次成功处理到的时间点，没有记录时返回 None
+pub async fn get_cursor(app_state: &AppState, job_name: &str) -> Result<Option<DateTime<Utc>>> {
+    let cursor = find_cursor(app_state, job_name).await?;
+
+    Ok(cursor.map(|cursor| DateTime::<Utc>::from_naive_utc_and_offset(cursor.high_water_mark, Utc)))
+}
+
+/// 从游标到 `until` 的时间范围，没有游标时从一天前开始
+///
+/// 游标已经在 `until` 之后时返回 None，说明这段时间已经处理过
+pub async fn cursor_window(app_state: &AppState, job_name: &str, until: DateTime<Utc>) -> Result<Option<TimeWindow>> {
+    let since = match get_cursor(app_state, job_name).await? {
+        Some(cursor) => cursor,
+        None => until.checked_sub_days(Days::new(1)).unwrap(),
+    };
+
+    if since >= until {
+        return Ok(None);
+    }
+
+    Ok(Some(TimeWindow::new(since, until)?))
+}
+
+/// 推进游标，游标只前进不后退
+pub async fn save_cursor(app_state: &AppState, job_name: &str, time: DateTime<Utc>) -> Result<()> {
+    match find_cursor(app_state, job_name).await? {
+        Some(cursor) => {
+            // 补跑和定时运行可能乱序完成
+            if cursor.high_water_mark >= time.naive_utc() {
+                return Ok(());
+            }
+
+            let mut cursor = cursor.into_active_model();
+            cursor.high_water_mark = Set(time.naive_utc());
+            cursor.updated_at = Set(Utc::now().naive_utc());
+            cursor.update(&app_state.mysql).await?;
+        }
+        None => {
+            let new_cursor = entity::job_cursor::ActiveModel {
+                id: NotSet,
+                job_name: Set(job_name.to_string()),
+                high_water_mark: Set(time.naive_utc()),
+                updated_at: Set(Utc::now().naive_utc()),
+            };
+            new_cursor.insert(&app_state.mysql).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 锁住游标，从读取游标、运行任务到保存游标期间，其他运行不能使用同一个游标，
+/// 否则时间范围重叠，同样的内容会重复发布。已被锁住时返回 false
+pub async fn lock_cursor(app_state: &AppState, job_name: &str) -> Result<bool> {
+    cache::put_nx_ttl(&app_state.redis, &cursor_lock_key(job_name), "1", CURSOR_LOCK_TTL_SEC).await
+}
+
+pub async fn unlock_cursor(app_state: &AppState, job_name: &str) -> Result<()> {
+    cache::del(&app_state.redis, &cursor_lock_key(job_name)).await
+}
+
+fn cursor_lock_key(job_name: &str) -> String {
+    format!("job_cursor_lock_{}", job_name)
+}
+
+async fn find_cursor(app_state: &AppState, job_name: &str) -> Result<Option<entity::job_cursor::Model>> {
+    let cursor = entity::job_cursor::Entity::find()
+        .filter(entity::job_cursor::Column::JobName.eq(job_name))
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(cursor)
+}
@@ -0,0 +1,359 @@
+use std::{str::FromStr, time::Duration};
+
+use actix_rt::spawn;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use log::{error, info, warn};
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, EntityTrait, PaginatorTrait};
+
+use crate::{
+    AppState,
+    tasks::{
+        github_task::repo::{DEFAULT_REPO, GithubRepo, disabled_repo_names, load_repo},
+        job_cursor::{cursor_window, get_cursor, lock_cursor, save_cursor, unlock_cursor},
+        task_run::{STATUS_SUCCESS, TaskName, Trigger, execute_run},
+    },
+};
+
+// 调度器检查间隔
+const TICK_SEC: u64 = 30;
+// 启动时每个任务最多补跑的次数，停机太久时更早的时间段不再补
+const MAX_CATCH_UP_RUNS: usize = 7;
+
+/// 首次启动时写入 `job_schedule` 表的默认任务，时间为本地时间
+/// 表达式格式：秒 分 时 日 月 星期
+const DEFAULT_JOBS: [(TaskName, &str); 7] = [
+    (TaskName::Issues, "0 0 12 * * *"),
+    (TaskName::Commits, "0 0 12 * * *"),
+    (TaskName::Prs, "0 0 12 * * *"),
+    (TaskName::MergeTrain, "0 20 12 * * *"),
+    (TaskName::Milestones, "0 0 13 * * *"),
+    // 新版本需要尽快发布，每小时检查一次
+    (TaskName::Releases, "0 30 * * * *"),
+    (TaskName::MigrationGuide, "0 0 14 * * *"),
+];
+
+/// 已注册的定时任务
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    pub task: TaskName,
+    pub schedule: Schedule,
+    /// 没有指定时使用默认仓库
+    pub repo: Option<String>,
+    /// 游标名称，由任务和仓库决定，同一任务的多个定时任务和手动运行共用一个游标，
+    /// 不会重复处理同一段时间
+    pub cursor: String,
+}
+
+impl Job {
+    pub fn from_model(model: &entity::job_schedule::Model) -> Result<Self> {
+        let task = TaskName::from_str(&model.task)?;
+        let repo = GithubRepo::parse(model.repo.as_deref().unwrap_or(DEFAULT_REPO))?;
+
+        Ok(Self {
+            name: model.name.clone(),
+            task,
+            schedule: parse_schedule(&model.cron)?,
+            repo: model.repo.clone(),
+            cursor: repo.job_name(task),
+        })
+    }
+
+    pub fn repo_name(&self) -> &str {
+        self.repo.as_deref().unwrap_or(DEFAULT_REPO)
+    }
+
+    /// 在 (last_tick, now] 之间需要触发的时间点
+    pub fn due_time(&self, last_tick: DateTime<Local>, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.schedule
+            .after(&last_tick)
+            .next()
+            .filter(|time| *time <= now)
+    }
+
+    /// 游标之后、现在之前错过的触发时间点，以及第一次补跑的开始时间
+    ///
+    /// 错过的次数超出上限时，开始时间移到最早保留的触发时间之前的一次，
+    /// 更早的时间段直接丢弃，不会合并到第一次补跑中
+    pub fn missed_times(&self, cursor: DateTime<Local>, now: DateTime<Local>) -> (DateTime<Local>, Vec<DateTime<Local>>) {
+        let mut times = self.schedule
+            .after(&cursor)
+            .take_while(|time| *time <= now)
+            .collect::<Vec<_>>();
+
+        // 不按时间范围抓取的任务只需要补跑一次
+        let max_runs = if self.task.supports_window() { MAX_CATCH_UP_RUNS } else { 1 };
+        let mut start = cursor;
+        if times.len() > max_runs {
+            warn!("定时任务 {} 错过了 {} 次，只补跑最近的 {} 次", self.name, times.len(), max_runs);
+            let skipped = times.drain(..times.len() - max_runs).collect::<Vec<_>>();
+            start = *skipped.last().unwrap();
+        }
+
+        (start, times)
+    }
+}
+
+pub fn parse_schedule(cron: &str) -> Result<Schedule> {
+    Schedule::from_str(cron).map_err(|err| anyhow::anyhow!("定时表达式错误: {}, {}", cron, err))
+}
+
+/// 表为空时写入默认任务
+pub async fn init_job_schedules(app_state: &AppState) -> Result<()> {
+    let count = entity::job_schedule::Entity::find()
+        .count(&app_state.mysql)
+        .await?;
+
+    if count > 0 {
+        return Ok(());
+    }
+
+    for (task, cron) in DEFAULT_JOBS {
+        let new_job = entity::job_schedule::ActiveModel {
+            id: NotSet,
+            name: Set(task.as_str().to_string()),
+            task: Set(task.as_str().to_string()),
+            cron: Set(cron.to_string()),
+            enabled: Set(true),
+            updated_at: Set(Utc::now().naive_utc()),
+            repo: Set(None),
+        };
+        new_job.insert(&app_state.mysql).await?;
+    }
+
+    info!("已写入默认定时任务");
+
+    Ok(())
+}
+
+/// 读取已启用的任务，配置错误的任务和已停用仓库的任务会被跳过
+async fn load_enabled_jobs(app_state: &AppState) -> Result<Vec<Job>> {
+    let models = entity::job_schedule::Entity::find()
+        .all(&app_state.mysql)
+        .await?;
+
+    let disabled_repos = disabled_repo_names(app_state).await?;
+
+    let jobs = models
+        .iter()
+        .filter(|model| model.enabled)
+        .filter_map(|model| match Job::from_model(model) {
+            Ok(job) => Some(job),
+            Err(err) => {
+                error!("定时任务 {} 配置错误: {err:?}", model.name);
+                None
+            }
+        })
+        .filter(|job| !disabled_repos.iter().any(|repo| repo == job.repo_name()))
+        .collect();
+
+    Ok(jobs)
+}
+
+/// 运行一次定时任务，处理 [游标, until) 之间的数据，成功后把游标推进到 until
+///
+/// 返回是否可以继续补跑，游标正在被其他运行使用时跳过这次运行
+async fn run_scheduled(app_state: AppState, job: &Job, until: DateTime<Utc>) -> Result<bool> {
+    if !lock_cursor(&app_state, &job.cursor).await? {
+        info!("定时任务 {} 正在运行，跳过", job.name);
+        return Ok(false);
+    }
+
+    let result = run_with_cursor(&app_state, job, until).await;
+    unlock_cursor(&app_state, &job.cursor).await?;
+
+    result
+}
+
+/// 已经锁住游标时运行
+async fn run_with_cursor(app_state: &AppState, job: &Job, until: DateTime<Utc>) -> Result<bool> {
+    let Some(window) = cursor_window(app_state, &job.cursor, until).await? else {
+        info!("定时任务 {} 在 {} 之前的数据已处理，跳过", job.name, until);
+        return Ok(true);
+    };
+
+    let repo = load_repo(app_state, job.repo.as_deref()).await?;
+    let run = execute_run(app_state.clone(), &job.name, job.task, repo, Trigger::Schedule, window).await?;
+
+    if run.status != STATUS_SUCCESS {
+        return Ok(false);
+    }
+
+    save_cursor(app_state, &job.cursor, window.until).await?;
+
+    Ok(true)
+}
+
+/// 补跑停机期间错过的定时任务
+///
+/// 没有游标的任务（新增的任务）不补跑，只记录最近一次应触发的时间
+async fn catch_up_missed_runs(app_state: &AppState, now: DateTime<Local>) -> Result<()> {
+    for job in load_enabled_jobs(app_state).await? {
+        let Some(cursor) = get_cursor(app_state, &job.cursor).await? else {
+            if let Some(last_time) = job.schedule.after(&now).next_back() {
+                save_cursor(app_state, &job.cursor, last_time.to_utc()).await?;
+            }
+            continue;
+        };
+
+        let (start, missed_times) = job.missed_times(cursor.with_timezone(&Local), now);
+        if start.to_utc() > cursor {
+            info!("定时任务 {} 跳过 {} 之前的数据", job.name, start);
+            save_cursor(app_state, &job.cursor, start.to_utc()).await?;
+        }
+
+        for fire_time in missed_times {
+            info!("补跑定时任务: {}, 触发时间: {}", job.name, fire_time);
+
+            // 每次补跑处理到对应的触发时间，保证每天的总结分开发布
+            // 失败时停止补跑，游标不变，下次启动时重试
+            if !run_scheduled(app_state.clone(), &job, fire_time.to_utc()).await? {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 启动调度器，每次检查时重新读取 `job_schedule` 表，修改配置后无需重启
+pub fn spawn_scheduler(app_state: AppState) {
+    info!("启动定时任务调度器");
+
+    let now = Local::now();
+
+    let state = app_state.clone();
+    spawn(async move {
+        if let Err(err) = catch_up_missed_runs(&state, now).await {
+            error!("补跑定时任务失败: {err:?}");
+        }
+    });
+
+    spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(TICK_SEC));
+        let mut last_tick = now;
+
+        loop {
+            interval.tick().await;
+            let now = Local::now();
+
+            match load_enabled_jobs(&app_state).await {
+                Ok(jobs) => {
+                    for job in jobs {
+                        if job.due_time(last_tick, now).is_none() {
+                            continue;
+                        }
+                        info!("触发定时任务: {}", job.name);
+
+                        let state = app_state.clone();
+                        spawn(async move {
+                            // 任务的错误记录在 job_run 表中，这里只有保存记录失败
+                            if let Err(err) = run_scheduled(state, &job, Utc::now()).await {
+                                error!("{err:?}");
+                            }
+                        });
+                    }
+                }
+                Err(err) => error!("读取定时任务失败: {err:?}"),
+            }
+
+            last_tick = now;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, TimeZone};
+
+    use crate::tasks::{
+        job_registry::{DEFAULT_JOBS, Job, parse_schedule},
+        task_run::TaskName,
+    };
+
+    #[test]
+    fn test_default_jobs() {
+        for (_, cron) in DEFAULT_JOBS {
+            assert!(parse_schedule(cron).is_ok());
+        }
+        assert!(parse_schedule("every day").is_err());
+    }
+
+    #[test]
+    fn test_job_cursor() {
+        let model = |name: &str, repo: Option<&str>| entity::job_schedule::Model {
+            id: 1,
+            name: name.to_string(),
+            task: "issues".to_string(),
+            cron: "0 0 18 * * *".to_string(),
+            enabled: true,
+            updated_at: Local::now().naive_utc(),
+            repo: repo.map(str::to_string),
+        };
+
+        // 同一任务的多个定时任务共用游标，和手动运行一致
+        assert_eq!(Job::from_model(&model("issues-evening", None)).unwrap().cursor, "issues");
+        assert_eq!(
+            Job::from_model(&model("website-issues", Some("bevyengine/bevy-website"))).unwrap().cursor,
+            "issues@bevyengine/bevy-website"
+        );
+    }
+
+    fn job(task: TaskName) -> Job {
+        Job {
+            name: task.as_str().to_string(),
+            task,
+            schedule: parse_schedule("0 0 12 * * *").unwrap(),
+            repo: None,
+            cursor: task.as_str().to_string(),
+        }
+    }
+
+    fn time(day: u32, h: u32, m: u32, s: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 11, day, h, m, s).unwrap()
+    }
+
+    #[test]
+    fn test_due_time() {
+        let job = job(TaskName::Issues);
+
+        assert_eq!(job.due_time(time(10, 11, 59, 40), time(10, 12, 0, 10)), Some(time(10, 12, 0, 0)));
+        assert_eq!(job.due_time(time(10, 11, 59, 40), time(10, 12, 0, 0)), Some(time(10, 12, 0, 0)));
+        assert_eq!(job.due_time(time(10, 12, 0, 0), time(10, 12, 0, 30)), None);
+        assert_eq!(job.due_time(time(10, 11, 0, 0), time(10, 11, 59, 59)), None);
+    }
+
+    #[test]
+    fn test_missed_times() {
+        let job = job(TaskName::Issues);
+
+        // 停机期间错过了11日和12日
+        let (start, times) = job.missed_times(time(10, 12, 0, 0), time(12, 18, 0, 0));
+        assert_eq!(start, time(10, 12, 0, 0));
+        assert_eq!(times, vec![time(11, 12, 0, 0), time(12, 12, 0, 0)]);
+
+        assert!(job.missed_times(time(10, 12, 0, 0), time(11, 11, 0, 0)).1.is_empty());
+
+        // 最多补跑7次，保留最近的
+        let (_, times) = job.missed_times(time(1, 12, 0, 0), time(20, 18, 0, 0));
+        assert_eq!(times.len(), 7);
+        assert_eq!(times.last(), Some(&time(20, 12, 0, 0)));
+
+        // 不按时间范围抓取的任务只补跑一次
+        let (_, times) = self::job(TaskName::Milestones).missed_times(time(1, 12, 0, 0), time(20, 18, 0, 0));
+        assert_eq!(times, vec![time(20, 12, 0, 0)]);
+    }
+
+    #[test]
+    fn test_missed_times_long_outage() {
+        let job = job(TaskName::Issues);
+
+        // 停机30天，只补最近7天，第一次补跑从23日开始，不包含更早的数据
+        let cursor = Local.with_ymd_and_hms(2025, 10, 31, 12, 0, 0).unwrap();
+        let (start, times) = job.missed_times(cursor, time(30, 18, 0, 0));
+        assert_eq!(start, time(23, 12, 0, 0));
+        assert_eq!(times, (24..=30).map(|day| time(day, 12, 0, 0)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,326 @@
+use anyhow::Result;
+use futures_util::{StreamExt, TryStreamExt, stream};
+use log::{info, warn};
+
+use crate::{
+    bots::llm_client::{ChatMessage, LlmOutput, LlmProvider, LlmUsage, estimate_tokens},
+    tasks::glossary::Glossary,
+};
+
+// 同时请求 AI 的批次数量
+const CONCURRENT_REQUESTS: usize = 4;
+// 合并轮数上限，避免部分总结本身过长时无限合并
+const MAX_REDUCE_ROUNDS: usize = 4;
+// 每批内容至少保留的预算，提示词过长时也能放下一条
+const MIN_CHUNK_TOKENS: usize = 1000;
+
+/// 分批总结的提示词
+pub struct MapReducePrompt<'a> {
+    /// 每批内容的总结提示词，只有一批时直接作为最终总结
+    pub map: &'a str,
+    /// 合并部分总结的提示词
+    pub reduce: &'a str,
+    /// 每批内容前都附带的共享上下文，例如帖子串的主贴，最多占每批预算的一半
+    pub context: Option<&'a str>,
+}
+
+/// 内容超出模型预算时，先分批总结，再合并成最终结果
+///
+/// 返回的模型为所有参与回答的模型，逗号分隔，用量为所有请求之和
+///
+/// 每批只附带批内出现的术语，合并时附带所有条目中出现的术语。
+/// 每次请求的输出都会检查术语译法，`task` 用于日志
+pub async fn map_reduce(
+    llm: &dyn LlmProvider,
+    task: &str,
+    prompt: &MapReducePrompt<'_>,
+    glossary: &Glossary,
+    items: &[String],
+    max_tokens: Option<u32>,
+) -> Result<LlmOutput> {
+    let terms = glossary.relevant(&format!("{}\n\n{}", prompt.context.unwrap_or_default(), items.join("\n\n")));
+
+    // 按附带全部术语估算预算，每批实际附带的术语只会更少
+    let map_budget = llm.chunk_tokens().saturating_sub(estimate_tokens(&terms.inject(prompt.map))).max(MIN_CHUNK_TOKENS);
+    let chunks = match prompt.context {
+        Some(context) => with_context(truncate_tokens(context, map_budget / 2), items, map_budget),
+        None => split_chunks(items, map_budget),
+    };
+
+    if chunks.len() > 1 {
+        info!("内容超出预算 {} Token，分为{}批总结", map_budget, chunks.len());
+    }
+
+    let chunks = chunks
+        .into_iter()
+        .map(|content| {
+            let terms = terms.relevant(&content);
+            Chunk { prompt: terms.inject(prompt.map), content, terms }
+        })
+        .collect::<Vec<_>>();
+    let mut outputs = summarize_chunks(llm, task, &chunks, max_tokens).await?;
+    if outputs.len() == 1 {
+        return Ok(outputs.remove(0));
+    }
+
+    let reduce_prompt = terms.inject(prompt.reduce);
+    let reduce_budget = llm.chunk_tokens().saturating_sub(estimate_tokens(&reduce_prompt)).max(MIN_CHUNK_TOKENS);
+    let mut merged = vec![];
+    for _ in 0..MAX_REDUCE_ROUNDS {
+        let partials = outputs.iter().map(|output| output.text.clone()).collect::<Vec<_>>();
+        let chunks = split_chunks(&partials, reduce_budget);
+        info!("合并{}份部分总结，共{}批", partials.len(), chunks.len());
+
+        // 合并时检查部分总结中已经用到的术语，避免合并后又换了译法
+        let chunks = chunks
+            .into_iter()
+            .map(|content| Chunk { prompt: reduce_prompt.clone(), terms: terms.mentioned(&content), content })
+            .collect::<Vec<_>>();
+
+        merged.append(&mut outputs);
+        outputs = summarize_chunks(llm, task, &chunks, max_tokens).await?;
+        if outputs.len() == 1 {
+            break;
+        }
+    }
+
+    if outputs.len() > 1 {
+        anyhow::bail!("部分总结过长，{}轮合并后仍有{}份", MAX_REDUCE_ROUNDS, outputs.len());
+    }
+
+    let mut output = outputs.remove(0);
+    for partial in merged {
+        merge_output(&mut output, &partial);
+    }
+
+    Ok(output)
+}
+
+/// 按预算把条目分成多批，条目顺序不变，单个条目超出预算时截断
+pub fn split_chunks(items: &[String], budget: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+    let mut current_tokens = 0;
+
+    for item in items {
+        let mut item = item.as_str();
+        let mut tokens = estimate_tokens(item);
+        if tokens > budget {
+            warn!("单个条目约 {} Token，超出预算 {}，截断处理", tokens, budget);
+            item = truncate_tokens(item, budget);
+            tokens = estimate_tokens(item);
+        }
+
+        if current_tokens > 0 && current_tokens + tokens > budget {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(item);
+        current_tokens += tokens;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// 扣除上下文后分批，每批开头都附带上下文，没有条目时只总结上下文
+fn with_context(context: &str, items: &[String], budget: usize) -> Vec<String> {
+    let budget = budget.saturating_sub(estimate_tokens(context)).max(MIN_CHUNK_TOKENS);
+    let chunks = split_chunks(items, budget);
+    if chunks.is_empty() {
+        return vec![context.to_string()];
+    }
+
+    chunks
+        .into_iter()
+        .map(|chunk| format!("{}\n\n{}", context, chunk))
+        .collect()
+}
+
+/// 截取开头不超过预算的部分
+fn truncate_tokens(text: &str, budget: usize) -> &str {
+    let mut tokens = 0;
+    let mut ascii = 0;
+
+    for (index, c) in text.char_indices() {
+        if c.is_ascii() {
+            ascii += 1;
+            if ascii % 4 == 1 {
+                tokens += 1;
+            }
+        } else {
+            tokens += 1;
+        }
+
+        if tokens > budget {
+            return &text[..index];
+        }
+    }
+
+    text
+}
+
+/// 一批请求内容，`terms` 用于检查这一批的输出
+struct Chunk {
+    prompt: String,
+    content: String,
+    terms: Glossary,
+}
+
+async fn summarize_chunks(
+    llm: &dyn LlmProvider,
+    task: &str,
+    chunks: &[Chunk],
+    max_tokens: Option<u32>,
+) -> Result<Vec<LlmOutput>> {
+    stream::iter(chunks)
+        .map(|chunk| async move {
+            let chat_messages = vec![ChatMessage::system(&chunk.prompt), ChatMessage::user(&chunk.content)];
+
+            let output = llm.chat(&chat_messages, max_tokens).await?;
+            chunk.terms.check(task, &output.text);
+
+            Ok::<_, anyhow::Error>(output)
+        })
+        .buffered(CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await
+}
+
+fn merge_output(output: &mut LlmOutput, partial: &LlmOutput) {
+    if !output.model.split(',').any(|model| model == partial.model) {
+        output.model = format!("{},{}", output.model, partial.model);
+    }
+
+    output.usage = LlmUsage {
+        prompt_tokens: output.usage.prompt_tokens + partial.usage.prompt_tokens,
+        completion_tokens: output.usage.completion_tokens + partial.usage.completion_tokens,
+        total_tokens: output.usage.total_tokens + partial.usage.total_tokens,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+
+    use crate::{
+        bots::llm_client::{ChatMessage, LlmOutput, LlmProvider, LlmUsage},
+        tasks::{
+            glossary::{Glossary, GlossaryTerm},
+            map_reduce::{MapReducePrompt, map_reduce, split_chunks},
+        },
+    };
+
+    // 模拟后端：记录收到的用户内容，返回内容的前几个字符
+    struct EchoProvider {
+        chunk_tokens: usize,
+        received: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for EchoProvider {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn chunk_tokens(&self) -> usize {
+            self.chunk_tokens
+        }
+
+        async fn chat(&self, messages: &[ChatMessage], _max_tokens: Option<u32>) -> Result<LlmOutput> {
+            let content = messages.last().unwrap().content.clone();
+            self.received.lock().unwrap().push(format!("{}|{}", messages[0].content, content));
+
+            Ok(LlmOutput {
+                text: content.chars().take(8).collect(),
+                model: if messages[0].content.starts_with("map") { "map-model" } else { "reduce-model" }.to_string(),
+                usage: LlmUsage { prompt_tokens: 2, completion_tokens: 1, total_tokens: 3 },
+            })
+        }
+    }
+
+    #[test]
+    fn test_split_chunks() {
+        let items = vec!["a".repeat(40), "b".repeat(40), "c".repeat(80), "d".repeat(200)];
+
+        // 每条10、10、20、50个 Token
+        assert_eq!(
+            split_chunks(&items, 25),
+            vec![format!("{}\n\n{}", "a".repeat(40), "b".repeat(40)), "c".repeat(80), "d".repeat(100)]
+        );
+        assert_eq!(split_chunks(&items[..2], 100).len(), 1);
+        assert!(split_chunks(&[], 100).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_map_reduce() {
+        let prompt = MapReducePrompt { map: "map", reduce: "reduce", context: None };
+        // 每条750个 Token
+        let items = vec!["a".repeat(3000), "b".repeat(3000), "c".repeat(3000)];
+
+        // 放得下时只请求一次
+        let llm = EchoProvider { chunk_tokens: 10000, received: Mutex::new(vec![]) };
+        let output = map_reduce(&llm, "test", &prompt, &Glossary::default(), &items, None).await.unwrap();
+        assert_eq!(output.model, "map-model");
+        assert_eq!(llm.received.lock().unwrap().len(), 1);
+
+        // 每批只放得下一条，分3批总结后合并
+        let llm = EchoProvider { chunk_tokens: 1000, received: Mutex::new(vec![]) };
+        let output = map_reduce(&llm, "test", &prompt, &Glossary::default(), &items, None).await.unwrap();
+        assert_eq!(output.model, "reduce-model,map-model");
+        assert_eq!(output.usage.total_tokens, 12);
+
+        {
+            let received = llm.received.lock().unwrap();
+            assert_eq!(received.len(), 4);
+            assert_eq!(received[3], "reduce|aaaaaaaa\n\nbbbbbbbb\n\ncccccccc");
+        }
+
+        // 提示词附带内容中出现的术语
+        let glossary = Glossary::new(vec![
+            GlossaryTerm {
+                term: "render graph".to_string(),
+                translation: "渲染图".to_string(),
+                explanation: None,
+                variants: vec![],
+            },
+            GlossaryTerm { term: "system".to_string(), translation: "系统".to_string(), explanation: None, variants: vec![] },
+        ]);
+        let llm = EchoProvider { chunk_tokens: 10000, received: Mutex::new(vec![]) };
+        map_reduce(&llm, "test", &prompt, &glossary, &["fix render graph".to_string()], None).await.unwrap();
+
+        let received = llm.received.lock().unwrap();
+        assert!(received[0].contains("- render graph => 渲染图"));
+        assert!(!received[0].contains("系统"));
+    }
+
+    #[tokio::test]
+    async fn test_map_reduce_with_context() {
+        let prompt = MapReducePrompt { map: "map", reduce: "reduce", context: Some("main") };
+        let items = vec!["a".repeat(3000), "b".repeat(3000)];
+
+        // 每批都以上下文开头
+        let llm = EchoProvider { chunk_tokens: 1000, received: Mutex::new(vec![]) };
+        map_reduce(&llm, "test", &prompt, &Glossary::default(), &items, None).await.unwrap();
+        {
+            let received = llm.received.lock().unwrap();
+            assert_eq!(received.len(), 3);
+            assert!(received[0].starts_with(&format!("map|main\n\n{}", "a".repeat(100))));
+            assert!(received[1].starts_with(&format!("map|main\n\n{}", "b".repeat(100))));
+        }
+
+        // 没有回复时只总结上下文
+        let llm = EchoProvider { chunk_tokens: 1000, received: Mutex::new(vec![]) };
+        let output = map_reduce(&llm, "test", &prompt, &Glossary::default(), &[], None).await.unwrap();
+        assert_eq!(output.text, "main");
+    }
+}
@@ -1,2 +1,11 @@
 pub mod github_task;
 pub mod bsky_task;
+pub mod glossary;
+pub mod job_cursor;
+pub mod job_registry;
+pub mod map_reduce;
+pub mod prompt_template;
+pub mod subscription;
+pub mod summary_record;
+pub mod task_run;
+pub mod time_window;
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use chrono::Utc;
+use log::info;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::{NotSet, Set}, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::Serialize;
+
+use crate::AppState;
+
+/// 内置模板：名称、初始版本、内容，首次启动时写入 `prompt_template` 表
+///
+/// 初始版本沿用提示词移出代码前的版本号，已经生成的总结记录仍然能对应上，
+/// 修改内置模板时版本号加一
+pub const DEFAULT_TEMPLATES: &[(&str, i32, &str)] = &[
+    ("issues", 4, include_str!("../../prompts/issues.txt")),
+    ("prs", 3, include_str!("../../prompts/prs.txt")),
+    ("commits", 3, include_str!("../../prompts/commits.txt")),
+    ("commits-reduce", 2, include_str!("../../prompts/commits-reduce.txt")),
+    ("milestone-issue", 2, include_str!("../../prompts/milestone-issue.txt")),
+    ("merge-train", 3, include_str!("../../prompts/merge-train.txt")),
+    ("merge-train-reduce", 2, include_str!("../../prompts/merge-train-reduce.txt")),
+    ("release", 3, include_str!("../../prompts/release.txt")),
+    ("migration-guide", 4, include_str!("../../prompts/migration-guide.txt")),
+    ("migration-guide-reduce", 1, include_str!("../../prompts/migration-guide-reduce.txt")),
+    ("webhook-urgent", 2, include_str!("../../prompts/webhook-urgent.txt")),
+];
+
+/// 内置模板写入的版本
+pub const SOURCE_BUILTIN: &str = "builtin";
+/// 在后台修改的版本
+pub const SOURCE_ADMIN: &str = "admin";
+
+/// 模板中可以使用的变量：`{date}`、`{repo}`、`{items}`，其他花括号原样保留
+///
+/// `{items}` 替换为条目数量，不是条目内容，条目由用户消息提供
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptVars {
+    /// 总结日期，例如 `2025-11-10`
+    pub date: String,
+    /// 仓库全名 `owner/repo`
+    pub repo: String,
+    /// 本次提供给 AI 的条目数量，模板中的 `{items}` 替换为这个数字
+    pub items: usize,
+}
+
+impl PromptVars {
+    pub fn new(date: impl ToString, repo: &str, items: usize) -> Self {
+        Self { date: date.to_string(), repo: repo.to_string(), items }
+    }
+
+    /// 不按日期汇总的任务使用当天日期
+    pub fn today(repo: &str, items: usize) -> Self {
+        Self::new(Utc::now().date_naive(), repo, items)
+    }
+}
+
+/// 提示词模板的某个版本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub version: i32,
+    pub content: String,
+}
+
+impl PromptTemplate {
+    pub fn from_model(model: entity::prompt_template::Model) -> Self {
+        Self {
+            name: model.name,
+            version: model.version,
+            content: model.content,
+        }
+    }
+
+    pub fn render(&self, vars: &PromptVars) -> String {
+        self.content
+            .replace("{date}", &vars.date)
+            .replace("{repo}", &vars.repo)
+            .replace("{items}", &vars.items.to_string())
+    }
+
+    /// 记录到总结中的版本，例如 `issues-v3`
+    pub fn version_tag(&self) -> String {
+        format!("{}-v{}", self.name, self.version)
+    }
+}
+
+pub fn is_known_template(name: &str) -> bool {
+    DEFAULT_TEMPLATES.iter().any(|(template_name, _, _)| *template_name == name)
+}
+
+/// 内置模板，文件末尾的换行不属于提示词
+pub fn default_template(name: &str) -> Option<PromptTemplate> {
+    DEFAULT_TEMPLATES
+        .iter()
+        .find(|(template_name, _, _)| *template_name == name)
+        .map(|(name, version, content)| PromptTemplate {
+            name: name.to_string(),
+            version: *version,
+            content: content.trim_end().to_string(),
+        })
+}
+
+/// 读取模板的最新版本，数据库中还没有时使用内置模板
+pub async fn load_template(app_state: &AppState, name: &str) -> Result<PromptTemplate> {
+    if let Some(model) = latest_version(app_state, name).await? {
+        return Ok(PromptTemplate::from_model(model));
+    }
+
+    let Some(template) = default_template(name) else {
+        anyhow::bail!("提示词模板不存在: {}", name);
+    };
+
+    Ok(template)
+}
+
+pub async fn latest_version(app_state: &AppState, name: &str) -> Result<Option<entity::prompt_template::Model>> {
+    let model = entity::prompt_template::Entity::find()
+        .filter(entity::prompt_template::Column::Name.eq(name))
+        .order_by_desc(entity::prompt_template::Column::Version)
+        .one(&app_state.mysql)
+        .await?;
+
+    Ok(model)
+}
+
+/// 保存为模板的新版本，版本号在最新版本上加一
+pub async fn save_new_version(
+    app_state: &AppState,
+    name: &str,
+    content: &str,
+) -> Result<entity::prompt_template::Model> {
+    let version = match latest_version(app_state, name).await? {
+        Some(latest) => latest.version + 1,
+        None => default_template(name).map(|template| template.version + 1).unwrap_or(1),
+    };
+
+    let new_template = entity::prompt_template::ActiveModel {
+        id: NotSet,
+        name: Set(name.to_string()),
+        version: Set(version),
+        content: Set(content.to_string()),
+        source: Set(SOURCE_ADMIN.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+
+    Ok(new_template.insert(&app_state.mysql).await?)
+}
+
+/// 写入数据库中还没有的内置模板，内置模板升级后写入新版本，在后台修改过的模板不会被覆盖
+pub async fn init_prompt_templates(app_state: &AppState) -> Result<()> {
+    for (name, version, _) in DEFAULT_TEMPLATES {
+        if !should_seed(latest_version(app_state, name).await?.as_ref(), *version) {
+            continue;
+        }
+
+        let Some(template) = default_template(name) else {
+            continue;
+        };
+        let new_template = entity::prompt_template::ActiveModel {
+            id: NotSet,
+            name: Set(template.name),
+            version: Set(template.version),
+            content: Set(template.content),
+            source: Set(SOURCE_BUILTIN.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+        new_template.insert(&app_state.mysql).await?;
+
+        info!("已写入默认提示词模板: {}", name);
+    }
+
+    Ok(())
+}
+
+/// 没有任何版本，或者最新版本是更旧的内置模板时写入；最新版本是后台修改的就一直保留
+fn should_seed(latest: Option<&entity::prompt_template::Model>, builtin_version: i32) -> bool {
+    match latest {
+        None => true,
+        Some(latest) => latest.source == SOURCE_BUILTIN && latest.version < builtin_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::tasks::prompt_template::{
+        DEFAULT_TEMPLATES, PromptTemplate, PromptVars, SOURCE_ADMIN, SOURCE_BUILTIN, default_template, should_seed,
+    };
+
+    #[test]
+    fn test_should_seed() {
+        let latest = |version: i32, source: &str| entity::prompt_template::Model {
+            id: 1,
+            name: "issues".to_string(),
+            version,
+            content: "".to_string(),
+            source: source.to_string(),
+            created_at: Utc::now().naive_utc(),
+        };
+
+        assert!(should_seed(None, 4));
+        assert!(should_seed(Some(&latest(3, SOURCE_BUILTIN)), 4));
+        assert!(!should_seed(Some(&latest(4, SOURCE_BUILTIN)), 4));
+        // 后台修改的版本即使版本号更小也不覆盖
+        assert!(!should_seed(Some(&latest(3, SOURCE_ADMIN)), 5));
+    }
+
+    #[test]
+    fn test_render() {
+        let template = PromptTemplate {
+            name: "issues".to_string(),
+            version: 4,
+            content: "{repo} 在 {date} 共有{items}条，{{版本}} 和 {unknown} 保持不变".to_string(),
+        };
+        let vars = PromptVars::new("2025-11-10", "bevyengine/bevy", 3);
+
+        assert_eq!(template.render(&vars), "bevyengine/bevy 在 2025-11-10 共有3条，{{版本}} 和 {unknown} 保持不变");
+        assert_eq!(template.version_tag(), "issues-v4");
+    }
+
+    #[test]
+    fn test_default_templates() {
+        for (name, version, _) in DEFAULT_TEMPLATES {
+            let template = default_template(name).unwrap();
+            assert_eq!(template.version, *version);
+            assert!(!template.content.is_empty());
+            assert!(!template.content.ends_with('\n'));
+            // 提示词不再经过 format!，双花括号会原样发给模型
+            assert!(!template.content.contains("{{"), "{}", name);
+        }
+        assert_eq!(default_template("issues").unwrap().version_tag(), "issues-v4");
+
+        // 每日总结的模板带上日期和仓库
+        for name in ["commits", "commits-reduce", "merge-train", "merge-train-reduce"] {
+            let content = default_template(name).unwrap().render(&PromptVars::new("2025-11-10", "bevyengine/bevy", 3));
+            assert!(content.contains("2025-11-10"), "{}", name);
+        }
+        assert!(default_template("unknown").is_none());
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use log::{error, info};
+use octocrab::models::{issues::Issue, pulls::PullRequest};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState,
+    bots::publisher::{Publishers, Summary, SummaryKind, build_channel_publisher},
+    tasks::github_task::repo::GithubRepo,
+};
+
+/// 可以订阅的总结类型
+pub const SUBSCRIBABLE_KINDS: [SummaryKind; 2] = [SummaryKind::Issues, SummaryKind::Prs];
+
+/// 订阅的筛选条件，不同条件之间需要同时满足，同一条件内满足任意一个即可，空条件不限制
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionFilter {
+    /// 为空时同时订阅 Issue 和 PR
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// 在标题和内容中查找，不区分大小写
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl SubscriptionFilter {
+    pub fn from_model(model: &entity::subscription::Model) -> Result<Self> {
+        Ok(Self {
+            kinds: serde_json::from_str(&model.kinds)?,
+            labels: serde_json::from_str(&model.labels)?,
+            authors: serde_json::from_str(&model.authors)?,
+            keywords: serde_json::from_str(&model.keywords)?,
+        })
+    }
+
+    /// 至少需要一个标签、作者或关键词条件，避免订阅全部内容
+    pub fn validate(&self) -> Result<()> {
+        for kind in &self.kinds {
+            if !SUBSCRIBABLE_KINDS.iter().any(|subscribable| subscribable.as_str() == kind) {
+                anyhow::bail!("不支持订阅的类型: {}", kind);
+            }
+        }
+
+        if self.labels.is_empty() && self.authors.is_empty() && self.keywords.is_empty() {
+            anyhow::bail!("至少需要一个标签、作者或关键词");
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, kind: SummaryKind, item: &SubscriptionItem) -> bool {
+        let kind_matched = self.kinds.is_empty() || self.kinds.iter().any(|name| name == kind.as_str());
+
+        let label_matched = self.labels.is_empty()
+            || self.labels.iter().any(|label| item.labels.iter().any(|item_label| item_label.eq_ignore_ascii_case(label)));
+
+        let author_matched = self.authors.is_empty()
+            || self.authors.iter().any(|author| author.eq_ignore_ascii_case(&item.author));
+
+        let keyword_matched = self.keywords.is_empty() || {
+            let content = format!("{}\n{}", item.title, item.body.as_deref().unwrap_or("")).to_lowercase();
+            self.keywords.iter().any(|keyword| content.contains(&keyword.to_lowercase()))
+        };
+
+        kind_matched && label_matched && author_matched && keyword_matched
+    }
+}
+
+/// 参与订阅匹配的 Issue 或 PR
+#[derive(Debug, Clone)]
+pub struct SubscriptionItem {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub html_url: String,
+}
+
+impl SubscriptionItem {
+    pub fn from_issue(issue: &Issue) -> Self {
+        Self {
+            number: issue.number,
+            title: issue.title.clone(),
+            body: issue.body.clone(),
+            author: issue.user.login.clone(),
+            labels: issue.labels.iter().map(|label| label.name.clone()).collect(),
+            html_url: issue.html_url.to_string(),
+        }
+    }
+
+    pub fn from_pr(pr: &PullRequest) -> Self {
+        Self {
+            number: pr.number,
+            title: pr.title.clone().unwrap_or_default(),
+            body: pr.body.clone(),
+            author: pr.user.as_ref().map(|user| user.login.clone()).unwrap_or_default(),
+            labels: pr.labels.iter().flatten().map(|label| label.name.clone()).collect(),
+            html_url: pr.html_url.as_ref().map(|url| url.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// 订阅消息的内容，直接列出匹配的条目，不经过 AI
+pub fn render_items(items: &[&SubscriptionItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "- [#{} {}]({})\n  作者: {}，标签: {}",
+                item.number,
+                item.title,
+                item.html_url,
+                item.author,
+                item.labels.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 把匹配订阅的条目发送到订阅的频道，返回发送成功的订阅数量
+///
+/// 每日总结照常发布，单个订阅发送失败只记录日志
+pub async fn route_subscriptions(
+    app_state: &AppState,
+    repo: &GithubRepo,
+    kind: SummaryKind,
+    date: NaiveDate,
+    items: &[SubscriptionItem],
+) -> Result<usize> {
+    let subscriptions = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::Enabled.eq(true))
+        .all(&app_state.mysql)
+        .await?;
+
+    let mut delivered = 0;
+    for subscription in subscriptions {
+        let filter = match SubscriptionFilter::from_model(&subscription) {
+            Ok(filter) => filter,
+            Err(err) => {
+                error!("订阅 {} 配置错误: {err:?}", subscription.name);
+                continue;
+            }
+        };
+
+        let matched = items
+            .iter()
+            .filter(|item| filter.matches(kind, item))
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            continue;
+        }
+
+        let title = format!(
+            "订阅「{}」：{} {}",
+            subscription.name,
+            repo.daily_name(kind.as_str()),
+            date.format("%Y-%m-%d")
+        );
+        let summary = Summary::new(kind, &title, &render_items(&matched))
+            .with_links(matched.iter().map(|item| item.html_url.clone()).collect());
+
+        let publisher = match build_channel_publisher(app_state, &subscription.publisher, &subscription.channel).await {
+            Ok(publisher) => publisher,
+            Err(err) => {
+                error!("订阅 {} 创建发布渠道失败: {err:?}", subscription.name);
+                continue;
+            }
+        };
+        let mut publishers = Publishers::default();
+        publishers.push(publisher);
+
+        if publishers.publish(&summary).await.ensure_any_success().is_ok() {
+            info!("订阅 {} 发送 {} 条", subscription.name, matched.len());
+            delivered += 1;
+        }
+    }
+
+    Ok(delivered)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bots::publisher::SummaryKind,
+        tasks::subscription::{SubscriptionFilter, SubscriptionItem, render_items},
+    };
+
+    fn item() -> SubscriptionItem {
+        SubscriptionItem {
+            number: 21380,
+            title: "Shadow acne with large directional light".to_string(),
+            body: Some("Cascaded shadow maps show artifacts".to_string()),
+            author: "Alice".to_string(),
+            labels: vec!["A-Rendering".to_string(), "C-Bug".to_string()],
+            html_url: "https://github.com/bevyengine/bevy/issues/21380".to_string(),
+        }
+    }
+
+    fn filter(kinds: &[&str], labels: &[&str], authors: &[&str], keywords: &[&str]) -> SubscriptionFilter {
+        let to_vec = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        SubscriptionFilter {
+            kinds: to_vec(kinds),
+            labels: to_vec(labels),
+            authors: to_vec(authors),
+            keywords: to_vec(keywords),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let item = item();
+
+        assert!(filter(&[], &["A-Rendering"], &[], &[]).matches(SummaryKind::Issues, &item));
+        assert!(filter(&[], &["A-ECS", "a-rendering"], &[], &[]).matches(SummaryKind::Prs, &item));
+        assert!(!filter(&[], &["A-ECS"], &[], &[]).matches(SummaryKind::Issues, &item));
+
+        assert!(filter(&[], &[], &["alice"], &[]).matches(SummaryKind::Issues, &item));
+        assert!(filter(&[], &[], &[], &["CASCADED"]).matches(SummaryKind::Issues, &item));
+        assert!(!filter(&[], &[], &[], &["meshlet"]).matches(SummaryKind::Issues, &item));
+
+        // 条件之间需要同时满足
+        assert!(!filter(&[], &["A-Rendering"], &["bob"], &[]).matches(SummaryKind::Issues, &item));
+        assert!(!filter(&["prs"], &["A-Rendering"], &[], &[]).matches(SummaryKind::Issues, &item));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(filter(&["issues"], &["A-ECS"], &[], &[]).validate().is_ok());
+        assert!(filter(&["commits"], &["A-ECS"], &[], &[]).validate().is_err());
+        assert!(filter(&["issues"], &[], &[], &[]).validate().is_err());
+    }
+
+    #[test]
+    fn test_render_items() {
+        let item = item();
+        assert_eq!(
+            render_items(&[&item]),
+            "- [#21380 Shadow acne with large directional light](https://github.com/bevyengine/bevy/issues/21380)\n  作者: Alice，标签: A-Rendering, C-Bug"
+        );
+    }
+}
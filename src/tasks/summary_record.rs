@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::Utc;
+use log::error;
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}};
+use serde::Serialize;
+
+use crate::{
+    AppState,
+    bots::publisher::{PublishReport, Publishers, Summary},
+};
+
+/// 生成总结时的来源信息
+#[derive(Debug, Clone, Default)]
+pub struct Provenance {
+    /// 提供给 AI 的条目ID，例如 Issue 编号、Commit SHA
+    pub source_ids: Vec<String>,
+    pub prompt_version: String,
+    pub model: String,
+    /// AI 原始输出
+    pub raw_output: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PublishTarget<'a> {
+    publisher: &'a str,
+    id: Option<&'a str>,
+    error: Option<String>,
+}
+
+/// 发布总结并保存到 `summary` 表，至少一个渠道发布成功才返回 Ok
+pub async fn publish_and_record(
+    app_state: &AppState,
+    publishers: &Publishers,
+    summary: &Summary,
+    provenance: &Provenance,
+) -> Result<PublishReport> {
+    let report = publishers.publish(summary).await;
+
+    // 记录失败不影响发布结果
+    if let Err(err) = save_summary(app_state, summary, provenance, &report).await {
+        error!("保存总结记录失败: {err:?}");
+    }
+
+    report.ensure_any_success()
+}
+
+async fn save_summary(
+    app_state: &AppState,
+    summary: &Summary,
+    provenance: &Provenance,
+    report: &PublishReport,
+) -> Result<entity::summary::Model> {
+    let targets = report.outcomes
+        .iter()
+        .map(|outcome| PublishTarget {
+            publisher: &outcome.publisher,
+            id: outcome.result.as_ref().ok().and_then(|id| id.as_deref()),
+            error: outcome.result.as_ref().err().map(|err| format!("{err:?}")),
+        })
+        .collect::<Vec<_>>();
+
+    // QQ 发帖是异步的，接口只返回任务ID
+    let qq_task_id = report.outcomes
+        .iter()
+        .find(|outcome| outcome.publisher == "qq")
+        .and_then(|outcome| outcome.result.as_ref().ok().cloned().flatten());
+
+    let new_summary = entity::summary::ActiveModel {
+        id: NotSet,
+        kind: Set(summary.kind.as_str().to_string()),
+        title: Set(summary.title.clone()),
+        source_ids: Set(serde_json::to_string(&provenance.source_ids)?),
+        source_urls: Set(serde_json::to_string(&summary.links)?),
+        prompt_version: Set(provenance.prompt_version.clone()),
+        model: Set(provenance.model.clone()),
+        llm_output: Set(provenance.raw_output.clone()),
+        publish_result: Set(serde_json::to_string(&targets)?),
+        qq_task_id: Set(qq_task_id),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+
+    Ok(new_summary.insert(&app_state.mysql).await?)
+}
@@ -0,0 +1,370 @@
+use std::{ops::AddAssign, str::FromStr, time::Duration};
+
+use actix_rt::spawn;
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{ActiveModelTrait, ActiveValue::{NotSet, Set}, IntoActiveModel};
+
+use crate::{
+    AppState,
+    tasks::{
+        bsky_task::watch_merge_train_feed::run_merge_train_task,
+        github_task::{
+            repo::{GithubRepo, load_repo},
+            watch_commits::run_commits_task, watch_issue_list::run_issue_async_task,
+            watch_milestones::get_changed_milestone, watch_migration_guide::run_migration_guide_task,
+            watch_pr::run_pr_task,
+            watch_releases::run_release_task,
+        },
+        job_cursor::{cursor_window, lock_cursor, save_cursor, unlock_cursor},
+        time_window::TimeWindow,
+    },
+};
+
+/// 可以手动触发的任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskName {
+    Issues,
+    Commits,
+    Prs,
+    Milestones,
+    MergeTrain,
+    Releases,
+    MigrationGuide,
+}
+
+impl TaskName {
+    pub const ALL: [TaskName; 7] = [
+        TaskName::Issues,
+        TaskName::Commits,
+        TaskName::Prs,
+        TaskName::Milestones,
+        TaskName::MergeTrain,
+        TaskName::Releases,
+        TaskName::MigrationGuide,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskName::Issues => "issues",
+            TaskName::Commits => "commits",
+            TaskName::Prs => "prs",
+            TaskName::Milestones => "milestones",
+            TaskName::MergeTrain => "merge-train",
+            TaskName::Releases => "releases",
+            TaskName::MigrationGuide => "migration-guide",
+        }
+    }
+
+    /// 是否按时间范围抓取数据，其他任务根据数据库记录去重
+    pub fn supports_window(&self) -> bool {
+        matches!(self, TaskName::Issues | TaskName::Commits | TaskName::Prs)
+    }
+
+    /// 是否可以为任意仓库运行，其他任务只用于默认仓库
+    pub fn supports_repo(&self) -> bool {
+        matches!(
+            self,
+            TaskName::Issues | TaskName::Commits | TaskName::Prs | TaskName::Milestones | TaskName::Releases
+        )
+    }
+}
+
+impl FromStr for TaskName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TaskName::ALL
+            .into_iter()
+            .find(|name| name.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("未知的任务: {}", s))
+    }
+}
+
+/// 任务的触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Schedule,
+    Manual,
+    Webhook,
+}
+
+impl Trigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trigger::Schedule => "schedule",
+            Trigger::Manual => "manual",
+            Trigger::Webhook => "webhook",
+        }
+    }
+}
+
+/// `job_run` 表中的运行状态
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_SUCCESS: &str = "success";
+pub const STATUS_FAILED: &str = "failed";
+/// 部分条目处理失败，失败原因记录在 `error` 中
+pub const STATUS_PARTIAL: &str = "partial";
+
+/// 一次任务处理的条目数、AI 请求耗时、实际回答的模型和失败的条目
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskStats {
+    pub items: usize,
+    pub llm_latency: Duration,
+    /// 降级时可能有多个模型，不重复
+    pub models: Vec<String>,
+    /// 逐条处理的任务中失败条目的错误信息，不影响其他条目
+    pub failures: Vec<String>,
+}
+
+impl TaskStats {
+    pub fn new(items: usize, llm_latency: Duration) -> Self {
+        Self { items, llm_latency, models: vec![], failures: vec![] }
+    }
+
+    /// 记录一个失败的条目
+    pub fn add_failure(&mut self, item: &str, err: &anyhow::Error) {
+        error!("处理 {} 发生错误：{err:?}", item);
+        self.failures.push(format!("{}: {err:#}", item));
+    }
+
+    /// 有失败的条目时，全部失败记为失败，否则记为部分成功
+    pub fn status(&self) -> &'static str {
+        if self.failures.is_empty() {
+            STATUS_SUCCESS
+        } else if self.items == 0 {
+            STATUS_FAILED
+        } else {
+            STATUS_PARTIAL
+        }
+    }
+
+    /// `model` 可以是逗号分隔的多个模型
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.add_models(model.split(','));
+        self
+    }
+
+    /// 保存到 `job_run` 表的模型，逗号分隔
+    pub fn model(&self) -> Option<String> {
+        if self.models.is_empty() {
+            None
+        } else {
+            Some(self.models.join(","))
+        }
+    }
+
+    fn add_models<'a>(&mut self, models: impl IntoIterator<Item = &'a str>) {
+        for model in models {
+            let model = model.trim();
+            if !model.is_empty() && !self.models.iter().any(|exist| exist == model) {
+                self.models.push(model.to_string());
+            }
+        }
+    }
+}
+
+impl AddAssign for TaskStats {
+    fn add_assign(&mut self, other: Self) {
+        self.items += other.items;
+        self.llm_latency += other.llm_latency;
+        self.add_models(other.models.iter().map(String::as_str));
+        self.failures.extend(other.failures);
+    }
+}
+
+/// 执行一次任务，合并队列任务不属于任何仓库
+pub async fn run_task(app_state: AppState, name: TaskName, repo: GithubRepo, window: TimeWindow) -> Result<TaskStats> {
+    if !repo.is_default() && !name.supports_repo() {
+        anyhow::bail!("任务 {} 只能用于默认仓库", name.as_str());
+    }
+
+    match name {
+        TaskName::Issues => run_issue_async_task(app_state, repo, window).await,
+        TaskName::Commits => run_commits_task(app_state, repo, window).await,
+        TaskName::Prs => run_pr_task(app_state, repo, window).await,
+        TaskName::Milestones => get_changed_milestone(app_state, repo).await,
+        TaskName::MergeTrain => run_merge_train_task(app_state).await,
+        TaskName::Releases => run_release_task(app_state, repo).await,
+        TaskName::MigrationGuide => run_migration_guide_task(app_state, repo).await,
+    }
+}
+
+/// 运行任务，并把运行结果记录到 `job_run` 表
+pub async fn execute_run(
+    app_state: AppState,
+    job_name: &str,
+    name: TaskName,
+    repo: GithubRepo,
+    trigger: Trigger,
+    window: TimeWindow,
+) -> Result<entity::job_run::Model> {
+    let run = start_run(&app_state, job_name, trigger).await?;
+    finish_run(app_state, run, name, repo, window).await
+}
+
+/// 在后台运行任务，立即返回运行记录
+///
+/// 没有指定时间范围时，按时间范围抓取的任务从任务和仓库共用的游标处理到现在，成功后推进游标，
+/// 运行期间锁住游标，定时任务正在使用游标时返回错误；
+/// 指定时间范围时只补发这段时间，不影响游标；没有指定仓库时使用默认仓库
+pub async fn spawn_manual_run(
+    app_state: AppState,
+    name: TaskName,
+    repo: Option<&str>,
+    window: Option<TimeWindow>,
+) -> Result<entity::job_run::Model> {
+    let repo = load_repo(&app_state, repo).await?;
+    let job_name = repo.job_name(name);
+
+    let (window, use_cursor) = match window {
+        Some(window) => (window, false),
+        None if name.supports_window() => {
+            if !lock_cursor(&app_state, &job_name).await? {
+                anyhow::bail!("任务 {} 正在运行", job_name);
+            }
+
+            let window = match cursor_window(&app_state, &job_name, Utc::now()).await {
+                Ok(Some(window)) => window,
+                Ok(None) => {
+                    unlock_cursor(&app_state, &job_name).await?;
+                    anyhow::bail!("任务 {} 没有需要处理的时间范围", job_name);
+                }
+                Err(err) => {
+                    unlock_cursor(&app_state, &job_name).await?;
+                    return Err(err);
+                }
+            };
+            (window, true)
+        }
+        None => (TimeWindow::last_day(), false),
+    };
+
+    let run = match start_run(&app_state, &job_name, Trigger::Manual).await {
+        Ok(run) => run,
+        Err(err) => {
+            if use_cursor {
+                unlock_cursor(&app_state, &job_name).await?;
+            }
+            return Err(err);
+        }
+    };
+
+    info!("手动运行任务: {}, 运行ID: {}, 时间范围: {:?}", run.job_name, run.id, window);
+
+    let res = run.clone();
+    spawn(async move {
+        match finish_run(app_state.clone(), run, name, repo, window).await {
+            Ok(run) if use_cursor && run.status == STATUS_SUCCESS => {
+                if let Err(err) = save_cursor(&app_state, &job_name, window.until).await {
+                    error!("保存任务游标失败: {err:?}");
+                }
+            }
+            Ok(_) => {}
+            Err(err) => error!("保存任务运行记录失败: {err:?}"),
+        }
+
+        // 使用游标时在开始前已经锁住游标
+        if use_cursor && let Err(err) = unlock_cursor(&app_state, &job_name).await {
+            error!("释放任务游标锁失败: {err:?}");
+        }
+    });
+
+    Ok(res)
+}
+
+async fn start_run(app_state: &AppState, job_name: &str, trigger: Trigger) -> Result<entity::job_run::Model> {
+    let new_run = entity::job_run::ActiveModel {
+        id: NotSet,
+        job_name: Set(job_name.to_string()),
+        trigger: Set(trigger.as_str().to_string()),
+        status: Set(STATUS_RUNNING.to_string()),
+        started_at: Set(Utc::now().naive_utc()),
+        finished_at: Set(None),
+        error: Set(None),
+        items_processed: Set(None),
+        llm_latency_ms: Set(None),
+        model: Set(None),
+    };
+
+    Ok(new_run.insert(&app_state.mysql).await?)
+}
+
+async fn finish_run(
+    app_state: AppState,
+    run: entity::job_run::Model,
+    name: TaskName,
+    repo: GithubRepo,
+    window: TimeWindow,
+) -> Result<entity::job_run::Model> {
+    let result = run_task(app_state.clone(), name, repo, window).await;
+
+    let mut run = run.into_active_model();
+    run.finished_at = Set(Some(Utc::now().naive_utc()));
+    match result {
+        Ok(stats) => {
+            run.status = Set(stats.status().to_string());
+            if !stats.failures.is_empty() {
+                run.error = Set(Some(stats.failures.join("\n")));
+            }
+            run.items_processed = Set(Some(stats.items as i32));
+            run.llm_latency_ms = Set(Some(stats.llm_latency.as_millis() as i64));
+            run.model = Set(stats.model());
+        }
+        Err(err) => {
+            error!("{err:?}");
+            run.status = Set(STATUS_FAILED.to_string());
+            run.error = Set(Some(format!("{err:?}")));
+        }
+    }
+
+    Ok(run.update(&app_state.mysql).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use crate::tasks::task_run::{STATUS_FAILED, STATUS_PARTIAL, STATUS_SUCCESS, TaskName, TaskStats};
+
+    #[test]
+    fn test_task_name() {
+        for name in TaskName::ALL {
+            assert_eq!(TaskName::from_str(name.as_str()).unwrap(), name);
+        }
+        assert!(TaskName::from_str("bsky").is_err());
+        assert!(TaskName::Prs.supports_window());
+        assert!(!TaskName::MergeTrain.supports_window());
+        assert!(TaskName::Releases.supports_repo());
+        assert!(!TaskName::MigrationGuide.supports_repo());
+    }
+
+    #[test]
+    fn test_task_stats() {
+        let mut stats = TaskStats::default();
+        stats += TaskStats::new(1, Duration::from_millis(300)).with_model("deepseek-reasoner");
+        stats += TaskStats::new(2, Duration::from_millis(200)).with_model("deepseek-chat,deepseek-reasoner");
+        assert_eq!(
+            stats,
+            TaskStats::new(3, Duration::from_millis(500)).with_model("deepseek-reasoner,deepseek-chat")
+        );
+        assert_eq!(stats.model().as_deref(), Some("deepseek-reasoner,deepseek-chat"));
+        assert_eq!(TaskStats::default().model(), None);
+    }
+
+    #[test]
+    fn test_task_status() {
+        let mut stats = TaskStats::default();
+        assert_eq!(stats.status(), STATUS_SUCCESS);
+
+        let mut failed = TaskStats::default();
+        failed.add_failure("#1", &anyhow::anyhow!("请求失败"));
+        stats += failed.clone();
+        assert_eq!(stats.status(), STATUS_FAILED);
+        assert_eq!(stats.failures, vec!["#1: 请求失败".to_string()]);
+
+        stats += TaskStats::new(1, Duration::from_millis(100));
+        assert_eq!(stats.status(), STATUS_PARTIAL);
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+
+/// 任务抓取数据的时间范围 [since, until)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn new(since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Self> {
+        if since >= until {
+            anyhow::bail!("时间范围错误: {} >= {}", since, until);
+        }
+
+        Ok(Self { since, until })
+    }
+
+    /// 截止到现在的最近一天，定时任务默认使用
+    pub fn last_day() -> Self {
+        let until = Utc::now();
+        Self {
+            since: until.checked_sub_days(Days::new(1)).unwrap(),
+            until,
+        }
+    }
+
+    /// 解析 RFC3339 格式的时间，未提供时使用最近一天的对应边界
+    pub fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        let default = Self::last_day();
+
+        let until = match until {
+            Some(until) => DateTime::parse_from_rfc3339(until)?.to_utc(),
+            None => default.until,
+        };
+        let since = match since {
+            Some(since) => DateTime::parse_from_rfc3339(since)?.to_utc(),
+            None => until.checked_sub_days(Days::new(1)).unwrap(),
+        };
+
+        Self::new(since, until)
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        time >= self.since && time < self.until
+    }
+
+    /// 总结标题中使用的日期
+    pub fn date(&self) -> NaiveDate {
+        self.until.with_timezone(&Local).date_naive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use crate::tasks::time_window::TimeWindow;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse() {
+        let window = TimeWindow::parse(None, Some("2025-11-10T12:00:00Z")).unwrap();
+        assert_eq!(window.since, time("2025-11-09T12:00:00Z"));
+        assert_eq!(window.until, time("2025-11-10T12:00:00Z"));
+
+        let window = TimeWindow::parse(Some("2025-11-01T00:00:00+08:00"), Some("2025-11-10T12:00:00Z")).unwrap();
+        assert_eq!(window.since, time("2025-10-31T16:00:00Z"));
+
+        assert!(TimeWindow::parse(Some("2025-11-11T00:00:00Z"), Some("2025-11-10T12:00:00Z")).is_err());
+        assert!(TimeWindow::parse(Some("yesterday"), None).is_err());
+    }
+
+    #[test]
+    fn test_contains() {
+        let window = TimeWindow::parse(None, Some("2025-11-10T12:00:00Z")).unwrap();
+        assert!(window.contains(time("2025-11-09T12:00:00Z")));
+        assert!(window.contains(time("2025-11-10T00:00:00Z")));
+        assert!(!window.contains(time("2025-11-09T11:59:59Z")));
+        assert!(!window.contains(time("2025-11-10T12:00:00Z")));
+    }
+}
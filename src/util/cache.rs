@@ -1,6 +1,6 @@
 use std::env;
 
-use redis::{Client, AsyncCommands};
+use redis::{AsyncCommands, Client, ExistenceCheck, SetExpiry, SetOptions};
 use anyhow::Result;
 
 /// 缓存数据
@@ -19,6 +19,17 @@ pub async fn put_ttl(client: &Client, key: &str, value: &str, ttl: u64) -> Resul
     Ok(())
 }
 
+/// 不存在时才缓存数据 (有效时间)，返回是否写入成功
+pub async fn put_nx_ttl(client: &Client, key: &str, value: &str, ttl: u64) -> Result<bool> {
+    let mut conn = client.get_multiplexed_tokio_connection().await?;
+    let key = get_prefix_key(key);
+    let options = SetOptions::default()
+        .conditional_set(ExistenceCheck::NX)
+        .with_expiration(SetExpiry::EX(ttl));
+    let res: Option<String> = conn.set_options(key, value, options).await?;
+    Ok(res.is_some())
+}
+
 /// 获取缓存
 pub async fn get(client: &Client, key: &str) -> Result<Option<String>> {
     let mut conn = client.get_multiplexed_tokio_connection().await?;
@@ -1,6 +1,7 @@
 pub mod res;
 pub mod cache;
 pub mod error;
+pub mod password;
 
 use rand::{distr::Alphanumeric, Rng};
 
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::SaltString,
+};
+
+use crate::util::random_str;
+
+/// 生成密码哈希（Argon2id，PHC 字符串格式）
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::encode_b64(random_str(16).as_bytes()).map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    Ok(hash.to_string())
+}
+
+/// 校验密码，哈希格式错误时视为不匹配
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(password_hash) else {
+        return false;
+    };
+
+    Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::password::{hash_password, verify_password};
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_password("bevy").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("bevy", &hash));
+        assert!(!verify_password("unity", &hash));
+        assert!(!verify_password("bevy", "not a hash"));
+        assert_ne!(hash, hash_password("bevy").unwrap());
+    }
+}